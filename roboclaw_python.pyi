@@ -1,13 +1,570 @@
 from .roboclaw_python import *
+import array
 
 class Motor:
     M1 = 1
     M2 = 2
 
+class Direction:
+    Forward = 1
+    Backward = -1
+
+class StatusFlag:
+    """
+    Bits of the status word
+    """
+    EStop = 0x00000001
+    TemperatureError = 0x00000002
+    Temperature2Error = 0x00000004
+    MainVoltageHighError = 0x00000008
+    LogicVoltageHighError = 0x00000010
+    LogicVoltageLowError = 0x00000020
+    M1DriverFaultError = 0x00000040
+    M2DriverFaultError = 0x00000080
+    M1SpeedError = 0x00000100
+    M2SpeedError = 0x00000200
+    M1PositionError = 0x00000400
+    M2PositionError = 0x00000800
+    M1CurrentError = 0x00001000
+    M2CurrentError = 0x00002000
+    M1OverCurrentWarning = 0x00010000
+    M2OverCurrentWarning = 0x00020000
+    MainVoltageHighWarning = 0x00040000
+    MainVoltageLowWarning = 0x00080000
+    TemperatureWarning = 0x00100000
+    Temperature2Warning = 0x00200000
+    M1Home = 0x00400000
+    M2Home = 0x00800000
+    SpeedErrorLimitWarning = 0x01000000
+    PositionErrorLimitWarning = 0x02000000
+
+class Status:
+    """
+    Status word of the RoboClaw
+    """
+    bits: int
+
+    def __init__(self, bits: int) -> None: ...
+    def contains(self, flag: StatusFlag) -> bool:
+        """
+        Returns True if the flag is set.
+        """
+    def flags(self) -> list[StatusFlag]:
+        """
+        Returns all flags that are set.
+        """
+    @property
+    def normal(self) -> bool:
+        """
+        True if no flag is set.
+        """
+
+class CommStats:
+    """
+    Communication counters of a RoboClaw connection, every retry counts as its own packet
+    """
+    packets: int
+    timeouts: int
+    crc_errors: int
+    nacks: int
+    io_errors: int
+
+    @property
+    def errors(self) -> int:
+        """
+        Number of packets that failed for any reason.
+        """
+
+class EncoderMode:
+    """
+    Encoder mode flags of a single encoder
+    """
+    absolute: bool
+    reverse_motor: bool
+    reverse_encoder: bool
+    rc_analog: bool
+
+    def __init__(self, absolute: bool = False, reverse_motor: bool = False, reverse_encoder: bool = False, rc_analog: bool = False) -> None: ...
+    @staticmethod
+    def from_bits(bits: int) -> EncoderMode:
+        """
+        Decode the mode byte as returned by the RoboClaw.
+        """
+    @property
+    def bits(self) -> int:
+        """
+        The mode byte as sent to the RoboClaw.
+        """
+    @property
+    def quadrature(self) -> bool:
+        """
+        True if the encoder is a quadrature encoder and not an absolute one.
+        """
+
+class Model:
+    RoboClaw2x7A = 0
+    RoboClaw2x15A = 1
+    RoboClaw2x30A = 2
+    RoboClaw2x45A = 3
+    RoboClaw2x60A = 4
+    RoboClaw2x80A = 5
+    RoboClaw2x120A = 6
+    RoboClaw2x160A = 7
+    RoboClaw2x200A = 8
+
+    @property
+    def peak_current(self) -> float:
+        """
+        Peak current per channel in amps.
+        """
+
+class PinMode:
+    """
+    Modes of the S3, S4 and S5 pins. Not every mode is available on every pin.
+    """
+    Disabled = 0x00
+    EStopLatching = 0x01
+    EStop = 0x02
+    VoltageClamp = 0x03
+    Home = 0x04
+    LimitForward = 0x12
+    LimitReverse = 0x22
+    LimitBoth = 0x32
+    HomeUser = 0x62
+    HomeAuto = 0xE2
+
+class CtrlMode:
+    """
+    Modes of the CTRL1 and CTRL2 outputs
+    """
+    Disabled = 0
+    User = 1
+    VoltageClamp = 2
+    Brake = 3
+
+class PwmMode:
+    LockedAntiphase = 0
+    SignMagnitude = 1
+
+class Ctrl:
+    CTRL1 = 1
+    CTRL2 = 2
+
+class Defaults:
+    """
+    Default duty cycle accelerations and default speeds.
+    Used when the RoboClaw runs from RC/Analog input or after a serial timeout.
+    """
+    m1_duty_accel: int
+    m2_duty_accel: int
+    m1_speed: int
+    m2_speed: int
+
+    def __init__(self, m1_duty_accel: int = 0, m2_duty_accel: int = 0, m1_speed: int = 0, m2_speed: int = 0) -> None: ...
+
+class ControlMode:
+    RC = 0
+    Analog = 1
+    SimpleSerial = 2
+    PacketSerial = 3
+
+class BatteryMode:
+    Off = 0
+    Auto = 1
+    Cell2 = 2
+    Cell3 = 3
+    Cell4 = 4
+    Cell5 = 5
+    Cell6 = 6
+    Cell7 = 7
+
+class StandardConfig:
+    """
+    Standard config settings word. Bits that aren't exposed are kept as they were read.
+    Setting an invalid baud rate or packet address raises an error.
+    """
+    bits: int
+    mode: ControlMode
+    battery_mode: BatteryMode
+    baud_rate: int
+    """
+    Only used in the serial modes, one of 2400, 9600, 19200, 38400, 57600, 115200, 230400 or 460800
+    """
+    packet_address: int
+    """
+    Range is 128 to 135
+    """
+    swap_buttons: bool
+    multi_unit: bool
+
+    def __init__(self, bits: int) -> None: ...
+
+class ErrorKind:
+    Speed = 0
+    Position = 1
+
+class ErrorEvent:
+    """
+    Raised by the ErrorSupervisor when a speed or position error is above its threshold
+    """
+    kind: ErrorKind
+    motor: Motor
+    error: int
+    threshold: int
+
+class ErrorSupervisor:
+    """
+    Compares the speed and position errors against host-side thresholds, so a jammed wheel
+    can be detected before the RoboClaw reaches its own error limits and faults.
+    """
+    speed_thresholds: tuple[int, int] | None
+    position_thresholds: tuple[int, int] | None
+    address: int | None
+
+    def __init__(self, speed_thresholds: tuple[int, int] = None, position_thresholds: tuple[int, int] = None, interval: float = 0.1, address: int = None) -> None:
+        """
+        - speed_thresholds / position_thresholds: (M1, M2) thresholds, None to not supervise this error
+        - interval: minimum time in seconds between two reads in poll()
+        """
+    @staticmethod
+    def from_controller(roboclaw: RoboClaw, fraction: float = 0.8, interval: float = 0.1, address: int = None) -> ErrorSupervisor:
+        """
+        Create a supervisor whose thresholds are a fraction of the error limits configured on the RoboClaw.
+        The fraction needs to be above 0 and at most 1.
+        """
+    @property
+    def interval(self) -> float: ...
+    def poll(self, roboclaw: RoboClaw) -> list[ErrorEvent]:
+        """
+        Read the errors if the interval has elapsed since the last poll and return every threshold violation.
+        Call this from your control loop.
+        """
+    def check(self, roboclaw: RoboClaw) -> list[ErrorEvent]:
+        """
+        Read the errors right away and return every threshold violation.
+        """
+
+class DifferentialDrive:
+    """
+    Drives a differential (skid-steer) base with one RoboClaw using linear and angular velocities.
+    """
+    left: Motor
+    address: int | None
+
+    def __init__(self, roboclaw: RoboClaw, wheel_radius: float, track_width: float, counts_per_revolution: float, gear_ratio: float = 1.0, left: Motor = Motor.M1, address: int = None) -> None:
+        """
+        - wheel_radius: radius of the wheels in meters
+        - track_width: distance between the left and right wheels in meters
+        - counts_per_revolution: encoder counts per motor revolution
+        - gear_ratio: motor revolutions per wheel revolution
+        - left: motor driving the left wheels, the other one drives the right wheels
+        """
+    @property
+    def wheel_radius(self) -> float: ...
+    @property
+    def track_width(self) -> float: ...
+    @property
+    def counts_per_revolution(self) -> float: ...
+    @property
+    def gear_ratio(self) -> float: ...
+    def drive(self, linear: float, angular: float) -> bool:
+        """
+        Drive with a linear velocity in m/s and an angular velocity in rad/s (positive turns left).
+        """
+    def stop(self) -> bool:
+        """
+        Set the speed of both motors to zero.
+        """
+    def read_wheel_velocities(self) -> tuple[float, float]:
+        """
+        Read the left and right wheel velocities in m/s.
+        """
+    def read_velocity(self) -> tuple[float, float]:
+        """
+        Read the linear velocity in m/s and the angular velocity in rad/s of the base.
+        """
+    def read_wheel_distances(self) -> tuple[float, float]:
+        """
+        Read the distance each wheel travelled in meters since the encoders were last reset.
+        """
+
+class OdometryEstimate:
+    """
+    Pose and velocity of a differential base at a point in time
+    """
+    x: float
+    y: float
+    heading: float
+    linear: float
+    angular: float
+    timestamp: float
+    pose_covariance: list[float]
+    """
+    Diagonal of the (x, y, heading) covariance
+    """
+    twist_covariance: list[float]
+    """
+    Diagonal of the (linear, angular) covariance
+    """
+
+class Odometry:
+    """
+    Integrates the pose (x, y in meters, heading in rad) and velocity of a differential base from the accumulated encoder counts.
+    """
+    x: float
+    y: float
+    heading: float
+    linear: float
+    angular: float
+    timestamp: float
+    pose_covariance: list[float]
+    twist_covariance: list[float]
+
+    def __init__(self, wheel_radius: float, track_width: float, counts_per_revolution: float, gear_ratio: float = 1.0, left: Motor = Motor.M1, pose_covariance: list[float] = [0.0, 0.0, 0.0], twist_covariance: list[float] = [0.0, 0.0]) -> None: ...
+    @staticmethod
+    def from_drive(drive: DifferentialDrive, pose_covariance: list[float] = [0.0, 0.0, 0.0], twist_covariance: list[float] = [0.0, 0.0]) -> Odometry:
+        """
+        Create an odometry using the geometry of a DifferentialDrive.
+        """
+    def estimate(self) -> OdometryEstimate: ...
+    def reset(self, x: float = 0.0, y: float = 0.0, heading: float = 0.0) -> None:
+        """
+        Set the pose. The next update only sets the reference encoder counts.
+        """
+    def update(self, m1_counts: int, m2_counts: int, timestamp: float = None) -> OdometryEstimate:
+        """
+        Update the pose from the accumulated counts of both motors, e.g. from read_encoders().
+        The timestamp is in seconds and defaults to the time since the odometry was created.
+        """
+    def update_motor(self, motor: Motor, counts: int, timestamp: float = None) -> OdometryEstimate | None:
+        """
+        Store the accumulated counts of one motor, e.g. from read_encoder() or one half of read_encoders(). The pose is updated once both motors got new counts.
+        """
+    def update_from(self, roboclaw: RoboClaw, address: int = None) -> OdometryEstimate:
+        """
+        Read both encoders in a single transaction and update the pose.
+        """
+
+class Unit:
+    """
+    Unit of positions and distances. Speeds use the unit per second, except Revolutions which uses RPM.
+    Accelerations use the speed unit per second.
+    """
+    Counts = 0
+    Radians = 1
+    Revolutions = 2
+    Meters = 3
+
+class MotorUnits:
+    """
+    Converts between encoder counts and physical units for one motor
+    """
+    counts_per_revolution: float
+    """
+    Encoder counts per motor revolution
+    """
+    gear_ratio: float
+    """
+    Motor revolutions per output shaft revolution
+    """
+    wheel_diameter: float | None
+    """
+    Wheel diameter in meters, needed for Unit.Meters
+    """
+
+    def __init__(self, counts_per_revolution: float, gear_ratio: float = 1.0, wheel_diameter: float = None) -> None: ...
+    def convert_to_counts(self, value: float, unit: Unit) -> float: ...
+    def convert_from_counts(self, counts: float, unit: Unit) -> float: ...
+    def convert_speed_to_counts(self, value: float, unit: Unit) -> float: ...
+    def convert_speed_from_counts(self, counts: float, unit: Unit) -> float: ...
+
+class Segment:
+    """
+    A buffered speed, acceleration and distance command in encoder counts
+    """
+    speed: int
+    accel: int
+    distance: int
+
+    def __init__(self, speed: int, accel: int, distance: int) -> None: ...
+
+class TrajectoryPlanner:
+    """
+    Plans trapezoidal or, with a jerk limit, S-curve profiles and breaks them into segments
+    for RoboClaw.execute_trajectory(). All values are in encoder counts, counts/s, counts/s^2 and counts/s^3,
+    use MotorUnits to convert from physical units.
+    """
+    max_velocity: float
+    max_accel: float
+    max_jerk: float | None
+    steps: int
+    """
+    Number of commands a jerk limited ramp is split into
+    """
+
+    def __init__(self, max_velocity: float, max_accel: float, max_jerk: float = None, steps: int = 8) -> None: ...
+    def plan_move(self, distance: float) -> list[Segment]:
+        """
+        Plan a move of distance counts that starts and ends at rest.
+        """
+    def plan(self, targets: list[float], velocities: list[float] = None, start: float = 0.0) -> list[Segment]:
+        """
+        Plan a path through the target positions, starting at rest at start.
+        velocities are the speeds when passing each target and default to zero, the last one has to be zero.
+        The velocity also has to be zero where the direction changes.
+        """
+
+class Channel:
+    """
+    A value read periodically by the TelemetryPoller. Channels with a single value have NaN as value2.
+    """
+    Encoders = 0
+    """Encoder counts of M1 and M2"""
+    Speeds = 1
+    """Speeds of M1 and M2 in counts/s"""
+    Pwms = 2
+    """Duty cycles of M1 and M2 from -32767 to 32767"""
+    Currents = 3
+    """Currents of M1 and M2 in amps"""
+    MainBattery = 4
+    """Main battery voltage in volts"""
+    LogicBattery = 5
+    """Logic battery voltage in volts"""
+    Temperature = 6
+    """Temperature of the first sensor in degrees Celsius"""
+    Temperature2 = 7
+    """Temperature of the second sensor in degrees Celsius"""
+    Status = 8
+    """Status bits, see Status"""
+
+class TelemetryPoller:
+    """
+    Opens its own connection and reads the configured channels at fixed rates on a background thread.
+    The samples are timestamped (seconds since the UNIX epoch) and kept in a lock-free ring buffer,
+    once it is full the oldest samples are overwritten. Don't open the same port with a RoboClaw at the same time.
+    """
+    running: bool
+    capacity: int
+    samples: int
+    """
+    Number of samples taken since the poller was created
+    """
+    errors: int
+    """
+    Number of failed reads since the poller was created, polling continues after an error
+    """
+    last_error: str | None
+
+    def __init__(self, port_name: str, baud_rate: int, timeout: int, retries: int, address: int, rates: dict[Channel, float], capacity: int = 4096) -> None:
+        """
+        ### Detailed Description
+        - rates: the channels to poll and their rates in Hz
+        - capacity: number of samples kept in the ring buffer
+        """
+    def start(self) -> None:
+        """
+        Start polling on a background thread. Does nothing if it is already running.
+        """
+    def stop(self) -> None:
+        """
+        Stop polling and wait for the current read to finish. The poller can be started again.
+        """
+    def snapshot(self, channel: Channel = None) -> dict[str, array.array]:
+        """
+        Return the buffered samples without removing them, see drain() for the format.
+        """
+    def drain(self, channel: Channel = None) -> dict[str, array.array]:
+        """
+        Remove and return the samples that weren't drained before, oldest first.
+        The dict has the columns "timestamp", "channel", "value1" and "value2" as array.array,
+        numpy.asarray() wraps them without copying. channel only returns the samples of that channel.
+        """
+
+class RecordFormat:
+    Csv = 0
+    """
+    One row per sample with the columns timestamp, channel, value1 and value2. value2 is empty for channels with a single value.
+    """
+    Columnar = 1
+    """
+    Compact little endian binary file with a schema header (column names and types, channel names)
+    followed by blocks of columns. Load it with TelemetryRecorder.load().
+    """
+
+class TelemetryRecorder:
+    """
+    Writes the samples of a TelemetryPoller to a file on a background thread.
+    """
+    format: RecordFormat
+    path: str
+    interval: float
+    recording: bool
+    """
+    False once stopped or if writing failed
+    """
+    rows: int
+    """
+    Number of samples written since the recording started
+    """
+    missed: int
+    """
+    Number of samples overwritten in the ring buffer of the poller before they could be written,
+    increase the capacity of the poller or lower the interval if this isn't zero
+    """
+
+    def __init__(self, poller: TelemetryPoller, path: str, format: RecordFormat = RecordFormat.Csv, interval: float = 1.0) -> None:
+        """
+        ### Detailed Description
+        - interval: seconds between writes, in the columnar format every write is one block
+        """
+    def start(self) -> None:
+        """
+        Create (or overwrite) the file and record every sample the poller takes from now on.
+        """
+    def stop(self) -> None:
+        """
+        Write the remaining samples and close the file. Raises the error if writing failed while recording.
+        """
+    @staticmethod
+    def load(path: str) -> dict[str, array.array]:
+        """
+        Load a columnar recording in the same format as TelemetryPoller.drain().
+        """
+
+def trace_packets(enabled: bool = True) -> None:
+    """
+    Enable or disable logging every packet to the Python logger "roboclaw_python.packets" at DEBUG level.
+    Each transaction is logged as a TX and an RX line with the address, the command name and number,
+    the payload as hex, the CRC, the status (ack, nack, ok, timeout, crc mismatch) and the latency.
+    """
+
+def analyze(data: bytes) -> list[str]:
+    """
+    Decode sniffed packet serial traffic (requests and answers interleaved) and return one line per frame.
+    The decoder re-synchronizes on the address bytes 0x80 to 0x87, validates the CRCs and flags
+    malformed bytes and orphaned requests without an answer.
+    """
+
 class RoboClaw:  
     """
     Class to control one or multiple RoboClaws
     """
+    model: Model | None
+    """
+    Model of the RoboClaw, used to validate current limits (optional)
+    """
+
+    def __init__(self, port_name: str, baud_rate: int, timeout: int, retries: int, address: int, model: Model = None, capture: str = None) -> None:
+        """
+        ### Detailed Description
+        - capture (optional): path of a capture file that every sent and received byte is written to, with timestamps
+        """
+    @staticmethod
+    def replay(capture: str, address: int, retries: int = 1, model: Model = None) -> RoboClaw:
+        """
+        Replay a capture file as a fake device instead of opening a serial port, e.g. to reproduce a bug report.
+        Sent bytes have to match the capture, otherwise an error is raised. Reads return the captured answers.
+        """
 
     def set_speed(self, motor: Motor, speed: int, address: int = None) -> bool: 
         """
@@ -34,12 +591,26 @@ class RoboClaw:
         - speed: negative to turn left, positive to turn right
         - address (optional): address of the roboclaw with the motors on (default to RoboClaw.new(address))
         """
+    def arcade_drive(self, forward: int, turn: int, address: int = None) -> bool:
+        """
+        Drive and turn both motors with one call using the 7 bit commands.
+        
+        ### Detailed Description
+        - forward: 0 is full speed backwards, 64 is stop and 127 is full speed forward
+        - turn: 0 is full speed left, 64 is stop and 127 is full speed right
+        - returns False if a value is outside of 0 to 127
+        """
 
     #Encoder Commands
     def read_encoder(self, motor: Motor, address: int = None) -> int:
         """
         Reads and returns the encoder value of the specified motor
         """
+    def read_encoders(self, address: int = None) -> tuple[int, int]:
+        """
+        Reads the encoder values of both motors in a single transaction. Overflow and underflow are handled
+        for both motors, as long as the encoders move less than 2^31 counts between two reads.
+        """
     def read_encoder_speed(self, address: int = None) -> int:
         """
         Read encoder counter speed. Returned value is in pulses per second.
@@ -58,6 +629,10 @@ class RoboClaw:
         Read the pulses counted in the last 300th of a second. This is an unfiltered version of funciton read_encoder_speeds.
         This function can be used to make an independant PID routine. Value returned is in encoder counts per second.
         """
+    def read_speeds(self, address: int = None) -> tuple[int, int]:
+        """
+        Read the raw speeds of both motors in a single transaction. Values are in encoder counts per second.
+        """
     def read_avg_speed(self, motor: Motor, address: int = None) -> int:
         """
         Read M1 or M2 average speed. Returns the speed in encoder counts per second.
@@ -70,6 +645,42 @@ class RoboClaw:
         """
         Read calculated position error in encoder counts per second.
         """
+    def read_speed_errors(self, address: int = None) -> tuple[int, int]:
+        """
+        Read the speed errors of both motors in encoder counts per second.
+        """
+    def read_position_errors(self, address: int = None) -> tuple[int, int]:
+        """
+        Read the position errors of both motors in encoder counts.
+        """
+    def set_speed_error_limits(self, m1_limit: int, m2_limit: int, address: int = None) -> bool:
+        """
+        Set the speed error limits of both motors. The RoboClaw faults when the speed error is above the limit.
+        """
+    def read_speed_error_limits(self, address: int = None) -> tuple[int, int]:
+        """
+        Read the speed error limits of both motors.
+        """
+    def set_position_error_limits(self, m1_limit: int, m2_limit: int, address: int = None) -> bool:
+        """
+        Set the position error limits of both motors. The RoboClaw faults when the position error is above the limit.
+        """
+    def read_position_error_limits(self, address: int = None) -> tuple[int, int]:
+        """
+        Read the position error limits of both motors.
+        """
+    def read_encoder_mode(self, motor: Motor, address: int = None) -> EncoderMode:
+        """
+        Read the encoder mode of the specified motor.
+        """
+    def set_encoder_mode(self, motor: Motor, mode: EncoderMode, address: int = None) -> bool:
+        """
+        Set the encoder mode of the specified motor.
+        - absolute: absolute encoder instead of quadrature
+        - reverse_motor: reverse the motor relative direction
+        - reverse_encoder: reverse the encoder relative direction
+        - rc_analog: enable RC/Analog encoder support
+        """
 
     #Advanced Motor Control
     def set_velocity_pid(self, motor: Motor, qpps: int, p: int, i: int, d: int, address: int = None) -> bool:
@@ -85,6 +696,23 @@ class RoboClaw:
 
         QPPS is the speed of the encoder when the motor is at 100% power. P, I, D are the default values used after reset.
         """
+    def read_velocity_pid(self, motor: Motor, address: int = None) -> tuple[int, int, int, int]:
+        """
+        Read the velocity PID constants of the specified motor as (QPPS, P, I, D).
+        """
+    def set_position_pid(self, motor: Motor, p: int, i: int, d: int, max_integral: int, deadzone: int, min_position: int, max_position: int, address: int = None) -> bool:
+        """
+        Set the position PID constants of the specified motor used by the position commands.
+
+        ### Detailed Description
+        - max_integral: limits the integral windup
+        - deadzone: position error in encoder counts that is ignored
+        - min_position / max_position: limits of the position commands in encoder counts
+        """
+    def read_position_pid(self, motor: Motor, address: int = None) -> tuple[int, int, int, int, int, int, int]:
+        """
+        Read the position PID constants of the specified motor as (P, I, D, max_integral, deadzone, min_position, max_position).
+        """
     def set_speed_duty(self, motor: Motor, duty: int, address: int = None) -> bool:
         """
         Drive the specified motor using a duty cycle value. The duty cycle is used to control the speed of the motor 
@@ -97,6 +725,61 @@ class RoboClaw:
         without a quadrature encoder.
         The duty value is signed and the range -32767 to 32767.
         """
+    def drive_speed(self, m1_speed: int, m2_speed: int, address: int = None) -> bool:
+        """
+        Set the signed speed of both motors in encoder counts per second.
+        """
+    def drive_both(self, m1_speed: int, m2_speed: int, accel: int, distances: tuple[int, int] = None, buffered: bool = False, address: int = None) -> bool:
+        """
+        Set the signed speed of both motors with the given acceleration in a single packet,
+        so both wheels change speed at the same time. Speeds are in encoder counts per second.
+
+        ### Detailed Description
+        - distances (optional): (M1, M2) distances in encoder counts after which the motors stop
+        - buffered: add the command to the buffer instead of executing it immediately, requires distances
+        """
+    def set_duty_accel(self, motor: Motor, duty: int, accel: int, address: int = None) -> bool:
+        """
+        Drive the specified motor using a duty cycle value and ramp to it with the given acceleration
+        instead of jumping like set_speed_duty() does.
+        The duty value is signed and the range -32767 to 32767. The acceleration range is 0 to 655359.
+        """
+    def drive_duty_accel(self, duty: int, accel: int, address: int = None) -> bool:
+        """
+        Drive both motors using a duty cycle value and ramp to it with the given acceleration.
+        The duty value is signed and the range -32767 to 32767. The acceleration range is 0 to 655359.
+        """
+    def set_max_current(self, motor: Motor, current: float, min_current: float = None, address: int = None) -> bool:
+        """
+        Set the maximum current limit of the specified motor in amps. The RoboClaw uses 10mA units, so the value is rounded to 0.01A.
+        If RoboClaw.model is set the value can't be above the peak current of the model.
+        min_current sets the minimum current limit too, it can't be above current. If it is omitted the minimum
+        current limit set on the controller is read and kept.
+        """
+    def read_max_current(self, motor: Motor, address: int = None) -> float:
+        """
+        Read the maximum current limit of the specified motor in amps.
+        """
+    def read_min_current(self, motor: Motor, address: int = None) -> float:
+        """
+        Read the minimum current limit of the specified motor in amps.
+        """
+    def set_pwm_mode(self, mode: PwmMode, address: int = None) -> bool:
+        """
+        Set the PWM drive mode to locked antiphase or sign magnitude.
+        """
+    def read_pwm_mode(self, address: int = None) -> PwmMode:
+        """
+        Read the PWM drive mode.
+        """
+    def set_deadband(self, reverse: float, forward: float, address: int = None) -> bool:
+        """
+        Set the RC/Analog mode deadband in percent. The RoboClaw uses 0.1% steps, range is 0 to 25%.
+        """
+    def read_deadband(self, address: int = None) -> tuple[float, float]:
+        """
+        Read the RC/Analog mode reverse and forward deadband in percent.
+        """
 
     #Advanced Commands
     def set_serial_timeout(self, timeout: int, address: int = None) -> bool:
@@ -108,4 +791,241 @@ class RoboClaw:
     def read_serial_timeout(self, address: int = None) -> int:
         """
         Read the current serial timeout setting. Range is 0 to 255.
-        """
\ No newline at end of file
+        """
+    def read_status(self, address: int = None) -> Status:
+        """
+        Read the current unit status.
+        """
+    def read_version(self, address: int = None) -> str:
+        """
+        Read the firmware version, e.g. "USB Roboclaw 2x15a v4.2.8".
+        """
+    def write_settings_to_eeprom(self, address: int = None) -> bool:
+        """
+        Store the current settings in the EEPROM, so they are kept after a power cycle.
+        """
+
+    #Units
+    def set_units(self, motor: Motor, units: MotorUnits = None) -> None:
+        """
+        Set the units used to convert the values of the unit aware methods for the specified motor. None removes them.
+        Unit.Counts works without units.
+        """
+    def get_units(self, motor: Motor) -> MotorUnits | None: ...
+    def read_position(self, motor: Motor, unit: Unit, address: int = None) -> float:
+        """
+        Read the encoder value of the specified motor converted to the given unit.
+        """
+    def set_position(self, motor: Motor, position: float, unit: Unit, address: int = None) -> bool:
+        """
+        Set the encoder value of the specified motor in the given unit.
+        """
+    def read_velocity(self, motor: Motor, unit: Unit, address: int = None) -> float:
+        """
+        Read the encoder speed of the specified motor converted to the given unit.
+        """
+    def set_velocity(self, motor: Motor, speed: float, unit: Unit, accel: float = None, address: int = None) -> bool:
+        """
+        Drive the specified motor with a signed speed, optionally ramping to it with the given acceleration.
+        """
+    def move_distance(self, motor: Motor, speed: float, distance: float, unit: Unit, accel: float = None, buffered: bool = False, address: int = None) -> bool:
+        """
+        Drive the specified motor with a signed speed for the given distance, optionally with an acceleration.
+        If buffered is True the command is added to the buffer instead of executing it immediately.
+        """
+    def move_to_position(self, motor: Motor, position: float, speed: float, accel: float, deccel: float, unit: Unit, buffered: bool = False, address: int = None) -> bool:
+        """
+        Move the specified motor to a position using the given speed, acceleration and decceleration.
+        If buffered is True the command is added to the buffer instead of executing it immediately.
+        """
+
+    #Trajectories
+    def read_buffer_length(self, address: int = None) -> tuple[int, int]:
+        """
+        Read the number of commands in the buffer of both motors.
+        128 means the buffer is empty and the last command finished, 0 means the last command is executing.
+        """
+    def execute_trajectory(self, segments: list[Segment], motor: Motor = None, depth: int = 8, poll_interval: float = 0.01, wait: bool = True, address: int = None) -> int:
+        """
+        Send the segments as buffered speed, acceleration and distance commands, polling read_buffer_length()
+        to keep at most depth commands in the buffer. The first segment replaces whatever the motor is doing.
+
+        ### Detailed Description
+        - motor (optional): the motor to move, both motors run the same segments if it's None
+        - wait: block until the buffer is empty and the last command finished
+        - returns the number of commands sent
+
+        Other Python threads keep running while this waits. If sending fails or the wait is interrupted,
+        e.g. with Ctrl-C, both motors are stopped before the error is raised.
+        """
+
+    #Communication
+    def comm_stats(self) -> CommStats:
+        """
+        Packets sent and failed since the connection was opened or the counters were reset.
+        Doesn't talk to the RoboClaw.
+        """
+    def reset_comm_stats(self) -> None: ...
+
+    #Telemetry
+    def read_currents(self, address: int = None) -> tuple[float, float]:
+        """
+        Read the currents of M1 and M2 in amps.
+        """
+    def read_pwms(self, address: int = None) -> tuple[int, int]:
+        """
+        Read the duty cycles of M1 and M2, range -32767 to 32767.
+        """
+    def read_temperature(self, sensor: int = 1, address: int = None) -> float:
+        """
+        Read the temperature of the board in degrees Celsius. sensor is 1 or 2, the second sensor isn't available on every model.
+        """
+
+    #Defaults
+    def read_defaults(self, address: int = None) -> Defaults:
+        """
+        Read the default duty cycle accelerations and default speeds of both motors.
+        """
+    def write_defaults(self, defaults: Defaults, address: int = None) -> bool:
+        """
+        Write the default duty cycle accelerations and default speeds of both motors.
+        Use this to set the failsafe behaviour explicitly instead of relying on what is stored in the EEPROM.
+        """
+
+    #Standard Config
+    def read_standard_config(self, address: int = None) -> StandardConfig:
+        """
+        Read the standard config settings (mode, baud rate, packet address, battery mode, multi-unit and swap buttons).
+        """
+    def write_standard_config(self, config: StandardConfig, reconnect: bool = True, address: int = None) -> bool:
+        """
+        Write the standard config settings.
+        If reconnect is True and the config stays in packet serial mode, the connection switches to the new baud rate
+        and reads the config back from the new packet address. If the RoboClaw doesn't answer the old baud rate is restored
+        and an error is raised. The default address is updated to the new packet address.
+        """
+
+    #Battery
+    def read_main_battery_voltage(self, address: int = None) -> float:
+        """
+        Read the main battery voltage in volts.
+        """
+    def read_logic_battery_voltage(self, address: int = None) -> float:
+        """
+        Read the logic battery voltage in volts.
+        """
+    def set_battery_voltage_offsets(self, main: float, logic: float, address: int = None) -> bool:
+        """
+        Set the offsets in volts which are added to the main and logic battery readings. The RoboClaw uses 0.1V steps.
+        """
+    def read_battery_voltage_offsets(self, address: int = None) -> tuple[float, float]:
+        """
+        Read the main and logic battery voltage offsets in volts.
+        """
+    def set_current_blanking(self, m1: float, m2: float, address: int = None) -> bool:
+        """
+        Set the current blanking percentages of both motors. The RoboClaw uses 0.1% steps.
+        """
+    def read_current_blanking(self, address: int = None) -> tuple[float, float]:
+        """
+        Read the current blanking percentages of both motors.
+        """
+    def calibrate_main_battery_offset(self, reference_voltage: float, samples: int = 10, apply: bool = True, address: int = None) -> float:
+        """
+        Guided calibration of the main battery reading.
+        Averages `samples` readings of read_main_battery_voltage(), compares them to the reference voltage measured
+        with a multimeter and returns the corrected offset in volts. If apply is True the offset is written to the RoboClaw,
+        the logic battery offset is kept.
+        """
+
+    #Homing
+    def set_auto_home(self, motor: Motor, percentage: int, timeout: int, address: int = None) -> bool:
+        """
+        Set the duty cycle (open loop) or speed (closed loop) and the timeout the RoboClaw uses for auto homing the specified motor.
+        """
+    def read_auto_home(self, motor: Motor, address: int = None) -> tuple[int, int]:
+        """
+        Read the auto homing percentage and timeout of the specified motor.
+        """
+    def home(self, motor: Motor, direction: Direction, duty: int, timeout: float, address: int = None) -> bool:
+        """
+        Drive the specified motor towards its home switch until the home flag in read_status() is set.
+        Afterwards the motor is stopped and its encoder is set to zero.
+        Other Python threads keep running while waiting, and an interrupt like Ctrl-C stops the motor and is raised.
+
+        ### Detailed Description
+        - direction: direction to drive in to reach the home switch
+        - duty: duty cycle used while homing, range 0 to 32767
+        - timeout: time in seconds before giving up, returns False if the home switch wasn't reached
+        """
+
+    #User Memory
+    def read_user_word(self, index: int, address: int = None) -> int:
+        """
+        Read a 16 bit word from the user EEPROM memory at the given index.
+        """
+    def write_user_word(self, index: int, value: int, address: int = None) -> bool:
+        """
+        Write a 16 bit word to the user EEPROM memory at the given index.
+        """
+    def read_user_memory(self, address: int = None) -> dict[str, int | float | str]:
+        """
+        Read all key/value pairs stored in the user EEPROM memory.
+        The stored blob is checksummed, a mismatch raises an error. Returns an empty dict if nothing was stored yet.
+        """
+    def write_user_memory(self, values: dict[str, int | float | str], address: int = None) -> bool:
+        """
+        Replace the key/value pairs stored in the user EEPROM memory.
+        Keys and string values can be at most 255 bytes long.
+        """
+    def get_user_value(self, key: str, address: int = None) -> int | float | str | None:
+        """
+        Read a single value from the user EEPROM memory. Returns None if the key is not stored.
+        """
+    def set_user_value(self, key: str, value: int | float | str, address: int = None) -> bool:
+        """
+        Store a single value in the user EEPROM memory, keeping all other keys.
+        """
+    def remove_user_value(self, key: str, address: int = None) -> bool:
+        """
+        Remove a key from the user EEPROM memory. Returns False if the key wasn't stored.
+        """
+    def get_user_int(self, key: str, address: int = None) -> int | None:
+        """
+        Read an int from the user EEPROM memory. Raises an error if the stored value has another type.
+        """
+    def get_user_float(self, key: str, address: int = None) -> float | None:
+        """
+        Read a float from the user EEPROM memory. Ints are converted to floats.
+        """
+    def get_user_str(self, key: str, address: int = None) -> str | None:
+        """
+        Read a str from the user EEPROM memory. Raises an error if the stored value has another type.
+        """
+
+    #Pins and Outputs
+    def set_pin_modes(self, s3: PinMode, s4: PinMode, s5: PinMode, address: int = None) -> bool:
+        """
+        Set the modes of the S3, S4 and S5 pins (E-Stop, voltage clamp, home or limit switches).
+        """
+    def read_pin_modes(self, address: int = None) -> tuple[PinMode, PinMode, PinMode]:
+        """
+        Read the modes of the S3, S4 and S5 pins.
+        """
+    def set_ctrl_modes(self, ctrl1: CtrlMode, ctrl2: CtrlMode, address: int = None) -> bool:
+        """
+        Set the modes of the CTRL1 and CTRL2 outputs.
+        """
+    def read_ctrl_modes(self, address: int = None) -> tuple[CtrlMode, CtrlMode]:
+        """
+        Read the modes of the CTRL1 and CTRL2 outputs.
+        """
+    def set_ctrl(self, ctrl: Ctrl, value: int, address: int = None) -> bool:
+        """
+        Set the output value of CTRL1 or CTRL2. Only used when the output is in CtrlMode.User.
+        The range is 0 to 65535, e.g. to drive a brake or an indicator light.
+        """
+    def read_ctrl(self, ctrl: Ctrl, address: int = None) -> int:
+        """
+        Read the output value of CTRL1 or CTRL2.
+        """
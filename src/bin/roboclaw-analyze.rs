@@ -0,0 +1,82 @@
+//! Decodes sniffed packet serial traffic and prints the request/answer pairs.
+
+use anyhow::{anyhow, Context, Result};
+use roboclaw_python::roboclaw::{analyze, parse_capture, CaptureEvent, Frame};
+use std::io::Read;
+
+const USAGE: &str = "usage: roboclaw-analyze [--hex | --capture] [FILE]
+
+Reads raw bytes from FILE or stdin.
+  --hex      the input is text with hex bytes, e.g. \"80 10 0x1F\"
+  --capture  the input is a capture file written by RoboClaw(capture=...)";
+
+enum Input {
+    Binary,
+    Hex,
+    Capture,
+}
+
+fn main() -> Result<()> {
+    let mut input: Input = Input::Binary;
+    let mut path: Option<String> = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--hex" => input = Input::Hex,
+            "--capture" => input = Input::Capture,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(anyhow!("unexpected argument {}\n\n{}", arg, USAGE)),
+        }
+    }
+
+    let mut data: Vec<u8> = Vec::new();
+    match path.as_deref() {
+        Some(path) if path != "-" => data = std::fs::read(path).with_context(|| format!("couldn't read {}", path))?,
+        _ => {
+            std::io::stdin().read_to_end(&mut data)?;
+        }
+    }
+
+    let bytes: Vec<u8> = match input {
+        Input::Binary => data,
+        Input::Hex => parse_hex(&String::from_utf8(data)?)?,
+        Input::Capture => parse_capture(&String::from_utf8(data)?)?
+            .into_iter()
+            .flat_map(|event| match event {
+                CaptureEvent::Tx(bytes) | CaptureEvent::Rx(bytes) => bytes,
+                _ => Vec::new(),
+            })
+            .collect(),
+    };
+
+    let (mut transactions, mut orphaned, mut malformed) = (0, 0, 0);
+    for frame in analyze(&bytes) {
+        match frame {
+            Frame::Transaction { .. } => transactions += 1,
+            Frame::Orphaned { .. } => orphaned += 1,
+            Frame::Malformed { .. } => malformed += 1,
+        }
+        println!("{}", frame);
+    }
+    eprintln!(
+        "{} bytes: {} transactions, {} orphaned, {} malformed",
+        bytes.len(),
+        transactions,
+        orphaned,
+        malformed
+    );
+    Ok(())
+}
+
+fn parse_hex(text: &str) -> Result<Vec<u8>> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|byte| !byte.is_empty())
+        .map(|byte| {
+            let digits: &str = byte.trim_start_matches("0x").trim_start_matches("0X");
+            u8::from_str_radix(digits, 16).map_err(|_| anyhow!("invalid hex byte {}", byte))
+        })
+        .collect()
+}
@@ -0,0 +1,226 @@
+//! Settings file of `roboclaw config dump` and `roboclaw config apply`.
+//!
+//! Every field is optional, `apply` only writes the settings present in the file.
+
+use anyhow::Result;
+use roboclaw_python::roboclaw::{EncoderMode, Motor, PinMode, PwmMode, RoboClaw, StandardConfig};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Standard config settings word (commands 98 and 99), written last as it can change baud rate and address.
+    pub standard_config: Option<u16>,
+    pub pwm_mode: Option<PwmMode>,
+    /// Reverse and forward RC/Analog deadband in percent.
+    pub deadband: Option<[f32; 2]>,
+    /// Serial timeout in tenths of a second, 0 disables it.
+    pub serial_timeout: Option<u8>,
+    /// Modes of the S3, S4 and S5 pins.
+    pub pin_modes: Option<[PinMode; 3]>,
+    /// M1 and M2 speed error limits.
+    pub speed_error_limits: Option<[u32; 2]>,
+    /// M1 and M2 position error limits.
+    pub position_error_limits: Option<[u32; 2]>,
+    pub m1: Option<MotorConfig>,
+    pub m2: Option<MotorConfig>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MotorConfig {
+    /// Encoder mode bits (commands 92 and 93).
+    pub encoder_mode: Option<u8>,
+    /// Max current in amps.
+    pub max_current: Option<f32>,
+    /// Min current in amps, kept as configured if only `max_current` is given.
+    pub min_current: Option<f32>,
+    pub velocity_pid: Option<VelocityPid>,
+    pub position_pid: Option<PositionPid>,
+}
+
+/// Raw values as used by `set_velocity_pid`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VelocityPid {
+    pub qpps: i32,
+    pub p: i32,
+    pub i: i32,
+    pub d: i32,
+}
+
+/// Raw values as used by `set_position_pid`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PositionPid {
+    pub p: i32,
+    pub i: i32,
+    pub d: i32,
+    pub max_integral: u32,
+    pub deadzone: u32,
+    pub min_position: i32,
+    pub max_position: i32,
+}
+
+impl VelocityPid {
+    pub fn read(roboclaw: &mut RoboClaw, motor: Motor) -> Result<Self> {
+        let (qpps, p, i, d) = roboclaw.read_velocity_pid(motor, None)?;
+        Ok(Self { qpps, p, i, d })
+    }
+
+    pub fn write(&self, roboclaw: &mut RoboClaw, motor: Motor) -> Result<()> {
+        roboclaw.set_velocity_pid(motor, self.qpps, self.p, self.i, self.d, None)?;
+        Ok(())
+    }
+}
+
+impl PositionPid {
+    pub fn read(roboclaw: &mut RoboClaw, motor: Motor) -> Result<Self> {
+        let (p, i, d, max_integral, deadzone, min_position, max_position) = roboclaw.read_position_pid(motor, None)?;
+        Ok(Self {
+            p,
+            i,
+            d,
+            max_integral,
+            deadzone,
+            min_position,
+            max_position,
+        })
+    }
+
+    pub fn write(&self, roboclaw: &mut RoboClaw, motor: Motor) -> Result<()> {
+        roboclaw.set_position_pid(
+            motor,
+            self.p,
+            self.i,
+            self.d,
+            self.max_integral,
+            self.deadzone,
+            self.min_position,
+            self.max_position,
+            None,
+        )?;
+        Ok(())
+    }
+}
+
+impl MotorConfig {
+    fn read(roboclaw: &mut RoboClaw, motor: Motor) -> Result<Self> {
+        Ok(Self {
+            encoder_mode: Some(roboclaw.read_encoder_mode(motor, None)?.bits()),
+            max_current: Some(roboclaw.read_max_current(motor, None)?),
+            min_current: Some(roboclaw.read_min_current(motor, None)?),
+            velocity_pid: Some(VelocityPid::read(roboclaw, motor)?),
+            position_pid: Some(PositionPid::read(roboclaw, motor)?),
+        })
+    }
+
+    fn write(&self, roboclaw: &mut RoboClaw, motor: Motor) -> Result<()> {
+        if let Some(bits) = self.encoder_mode {
+            roboclaw.set_encoder_mode(motor, EncoderMode::from_bits(bits), None)?;
+        }
+        if self.max_current.is_some() || self.min_current.is_some() {
+            let current: f32 = match self.max_current {
+                Some(current) => current,
+                None => roboclaw.read_max_current(motor, None)?,
+            };
+            roboclaw.set_max_current(motor, current, self.min_current, None)?;
+        }
+        if let Some(pid) = self.velocity_pid {
+            pid.write(roboclaw, motor)?;
+        }
+        if let Some(pid) = self.position_pid {
+            pid.write(roboclaw, motor)?;
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Reads every setting the file can hold.
+    pub fn read(roboclaw: &mut RoboClaw) -> Result<Self> {
+        let (s3, s4, s5) = roboclaw.read_pin_modes(None)?;
+        let (reverse, forward) = roboclaw.read_deadband(None)?;
+        let (m1_speed, m2_speed) = roboclaw.read_speed_error_limits(None)?;
+        let (m1_position, m2_position) = roboclaw.read_position_error_limits(None)?;
+
+        Ok(Self {
+            standard_config: Some(roboclaw.read_standard_config(None)?.bits),
+            pwm_mode: Some(roboclaw.read_pwm_mode(None)?),
+            deadband: Some([reverse, forward]),
+            serial_timeout: Some(roboclaw.read_serial_timeout(None)?),
+            pin_modes: Some([s3, s4, s5]),
+            speed_error_limits: Some([m1_speed, m2_speed]),
+            position_error_limits: Some([m1_position, m2_position]),
+            m1: Some(MotorConfig::read(roboclaw, Motor::M1)?),
+            m2: Some(MotorConfig::read(roboclaw, Motor::M2)?),
+        })
+    }
+
+    /// Writes the settings present in the file.
+    pub fn write(&self, roboclaw: &mut RoboClaw) -> Result<()> {
+        if let Some(m1) = &self.m1 {
+            m1.write(roboclaw, Motor::M1)?;
+        }
+        if let Some(m2) = &self.m2 {
+            m2.write(roboclaw, Motor::M2)?;
+        }
+        if let Some(mode) = self.pwm_mode {
+            roboclaw.set_pwm_mode(mode, None)?;
+        }
+        if let Some([reverse, forward]) = self.deadband {
+            roboclaw.set_deadband(reverse, forward, None)?;
+        }
+        if let Some(timeout) = self.serial_timeout {
+            roboclaw.set_serial_timeout(timeout, None)?;
+        }
+        if let Some([s3, s4, s5]) = self.pin_modes {
+            roboclaw.set_pin_modes(s3, s4, s5, None)?;
+        }
+        if let Some([m1, m2]) = self.speed_error_limits {
+            roboclaw.set_speed_error_limits(m1, m2, None)?;
+        }
+        if let Some([m1, m2]) = self.position_error_limits {
+            roboclaw.set_position_error_limits(m1, m2, None)?;
+        }
+        if let Some(bits) = self.standard_config
+            && roboclaw.read_standard_config(None)?.bits != bits
+        {
+            roboclaw.write_standard_config(StandardConfig::new(bits), true, None)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roboclaw_python::roboclaw::testing::{acked, answered, fake};
+
+    #[test]
+    fn mode_names() {
+        let text: &str = "pwm_mode = \"sign_magnitude\"\npin_modes = [\"disabled\", \"e_stop_latching\", \"home_auto\"]\n";
+        let config: Config = toml::from_str(text).unwrap();
+        assert_eq!(config.pwm_mode, Some(PwmMode::SignMagnitude));
+        assert_eq!(config.pin_modes, Some([PinMode::Disabled, PinMode::EStopLatching, PinMode::HomeAuto]));
+        assert_eq!(toml::to_string(&config).unwrap(), text);
+        assert!(toml::from_str::<Config>("pwm_mode = \"SignMagnitude\"").is_err());
+    }
+
+    #[test]
+    fn current_limits() {
+        // max 15A and min 0.5A configured, the file only sets the max or the min
+        let limits = || answered(vec![0x80, 135], vec![0, 0, 0x05, 0xDC, 0, 0, 0, 0x32]);
+        let mut roboclaw: RoboClaw = fake(vec![
+            limits(),
+            acked(vec![0x80, 133, 0, 0, 0x03, 0xE8, 0, 0, 0, 0x32]),
+            limits(),
+            acked(vec![0x80, 133, 0, 0, 0x05, 0xDC, 0, 0, 0, 0x64]),
+        ]);
+
+        let max_only: MotorConfig = toml::from_str("max_current = 10.0").unwrap();
+        max_only.write(&mut roboclaw, Motor::M1).unwrap();
+        let min_only: MotorConfig = toml::from_str("min_current = 1.0").unwrap();
+        min_only.write(&mut roboclaw, Motor::M1).unwrap();
+    }
+}
@@ -0,0 +1,335 @@
+//! Command line tool for setting up and testing a RoboClaw without Python or Motion Studio.
+
+mod config;
+mod monitor;
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use config::{Config, PositionPid, VelocityPid};
+use roboclaw_python::roboclaw::{Motor, RoboClaw, StatusFlag, Unit, ADDRESSES};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(name = "roboclaw", version, about = "Set up and test RoboClaw motor controllers")]
+struct Cli {
+    #[command(flatten)]
+    connection: ConnectionArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct ConnectionArgs {
+    /// Serial port, e.g. /dev/ttyACM0 or COM3
+    #[arg(short, long, global = true, default_value = "/dev/ttyACM0")]
+    port: String,
+    #[arg(short, long, global = true, default_value_t = 38400)]
+    baud: u32,
+    /// Packet serial address (128 - 135)
+    #[arg(short, long, global = true, default_value_t = 128, value_parser = clap::value_parser!(u8).range(128..=135))]
+    address: u8,
+    /// Read timeout in milliseconds
+    #[arg(long, global = true, default_value_t = 100)]
+    timeout: u32,
+    #[arg(long, global = true, default_value_t = 3)]
+    retries: u8,
+    /// Write every packet to a capture file
+    #[arg(long, global = true)]
+    capture: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the RoboClaws answering on the port
+    Scan,
+    /// Print firmware version, voltages, temperatures and status
+    Info,
+    /// Drive a motor with the simple commands, -127 (full reverse) to 127 (full forward)
+    Drive {
+        motor: MotorArg,
+        #[arg(allow_negative_numbers = true)]
+        speed: i8,
+    },
+    /// Drive a motor with a duty cycle in percent
+    Duty {
+        motor: MotorArg,
+        #[arg(allow_negative_numbers = true)]
+        percent: f32,
+    },
+    /// Drive a motor at a speed in encoder counts per second
+    Speed {
+        motor: MotorArg,
+        #[arg(allow_negative_numbers = true)]
+        qpps: i32,
+        /// Acceleration in counts per second squared
+        #[arg(long)]
+        accel: Option<u32>,
+    },
+    /// Stop both motors
+    Stop,
+    /// Print encoder counts and speeds continuously
+    Encoders {
+        /// Milliseconds between reads
+        #[arg(short, long, default_value_t = 100)]
+        interval: u64,
+        /// Stop after this many reads
+        #[arg(short, long)]
+        count: Option<u64>,
+    },
+    /// Reset both encoder counts to 0
+    ResetEncoders,
+    /// Live dashboard with keyboard jogging and an e-stop key
+    Monitor {
+        /// Milliseconds between reads
+        #[arg(short, long, default_value_t = 100)]
+        interval: u64,
+    },
+    /// Read or write the PID constants
+    #[command(subcommand)]
+    Pid(PidCommand),
+    /// Dump or apply a settings file
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Subcommand)]
+enum PidCommand {
+    /// Print the velocity and position PID constants
+    Get {
+        /// Only this motor, both if omitted
+        motor: Option<MotorArg>,
+    },
+    /// Set the velocity PID constants (raw values)
+    Velocity {
+        motor: MotorArg,
+        qpps: i32,
+        p: i32,
+        i: i32,
+        d: i32,
+        /// Store the settings in the EEPROM
+        #[arg(long)]
+        save: bool,
+    },
+    /// Set the position PID constants (raw values)
+    Position {
+        motor: MotorArg,
+        p: i32,
+        i: i32,
+        d: i32,
+        max_integral: u32,
+        deadzone: u32,
+        #[arg(allow_negative_numbers = true)]
+        min_position: i32,
+        #[arg(allow_negative_numbers = true)]
+        max_position: i32,
+        /// Store the settings in the EEPROM
+        #[arg(long)]
+        save: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Write the settings as TOML to FILE or stdout
+    Dump { file: Option<PathBuf> },
+    /// Write the settings present in a TOML file
+    Apply {
+        file: PathBuf,
+        /// Store the settings in the EEPROM
+        #[arg(long)]
+        save: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MotorArg {
+    M1,
+    M2,
+}
+
+impl From<MotorArg> for Motor {
+    fn from(motor: MotorArg) -> Self {
+        match motor {
+            MotorArg::M1 => Motor::M1,
+            MotorArg::M2 => Motor::M2,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let cli: Cli = Cli::parse();
+    let args: &ConnectionArgs = &cli.connection;
+    let mut roboclaw: RoboClaw = RoboClaw::new(
+        &args.port,
+        args.baud,
+        args.timeout,
+        args.retries,
+        args.address,
+        None,
+        args.capture.clone(),
+    )
+    .with_context(|| format!("couldn't open {}", args.port))?;
+
+    match cli.command {
+        Command::Scan => scan(&mut roboclaw),
+        Command::Info => info(&mut roboclaw),
+        Command::Drive { motor, speed } => {
+            if speed == i8::MIN {
+                return Err(anyhow!("invalid value: {} needs to be between -127 and 127", speed));
+            }
+            roboclaw.set_speed(motor.into(), speed, None)?;
+            Ok(())
+        }
+        Command::Duty { motor, percent } => {
+            if !(-100.0..=100.0).contains(&percent) {
+                return Err(anyhow!("invalid value: {} needs to be between -100 and 100 percent", percent));
+            }
+            roboclaw.set_speed_duty(motor.into(), (percent / 100.0 * i16::MAX as f32).round() as i16, None)?;
+            Ok(())
+        }
+        Command::Speed { motor, qpps, accel } => {
+            roboclaw.set_velocity(motor.into(), qpps as f64, Unit::Counts, accel.map(f64::from), None)?;
+            Ok(())
+        }
+        Command::Stop => {
+            roboclaw.set_speed_duty(Motor::M1, 0, None)?;
+            roboclaw.set_speed_duty(Motor::M2, 0, None)?;
+            Ok(())
+        }
+        Command::Encoders { interval, count } => encoders(&mut roboclaw, Duration::from_millis(interval), count),
+        Command::ResetEncoders => {
+            roboclaw.reset_encoders(None)?;
+            Ok(())
+        }
+        Command::Monitor { interval } => monitor::run(&mut roboclaw, args.address, Duration::from_millis(interval)),
+        Command::Pid(command) => pid(&mut roboclaw, command),
+        Command::Config(command) => config(&mut roboclaw, command),
+    }
+}
+
+fn scan(roboclaw: &mut RoboClaw) -> Result<()> {
+    let mut found: usize = 0;
+    for address in ADDRESSES {
+        if let Ok(version) = roboclaw.read_version(Some(address)) {
+            println!("{} (0x{:02X}): {}", address, address, version);
+            found += 1;
+        }
+    }
+    if found == 0 {
+        return Err(anyhow!("no RoboClaw answered"));
+    }
+    Ok(())
+}
+
+fn info(roboclaw: &mut RoboClaw) -> Result<()> {
+    println!("firmware:        {}", roboclaw.read_version(None)?);
+    println!("main battery:    {:.1} V", roboclaw.read_main_battery_voltage(None)?);
+    println!("logic battery:   {:.1} V", roboclaw.read_logic_battery_voltage(None)?);
+    println!("temperature:     {:.1} °C", roboclaw.read_temperature(1, None)?);
+    // only some models have a second sensor
+    match roboclaw.read_temperature(2, None) {
+        Ok(temperature) => println!("temperature 2:   {:.1} °C", temperature),
+        Err(_) => println!("temperature 2:   n/a"),
+    }
+
+    let status = roboclaw.read_status(None)?;
+    let flags: Vec<StatusFlag> = status.flags();
+    if flags.is_empty() {
+        println!("status:          normal");
+    } else {
+        let names: Vec<String> = flags.iter().map(|flag| format!("{:?}", flag)).collect();
+        println!("status:          {} (0x{:08X})", names.join(", "), status.bits);
+    }
+    Ok(())
+}
+
+fn encoders(roboclaw: &mut RoboClaw, interval: Duration, count: Option<u64>) -> Result<()> {
+    println!("{:>12} {:>12} {:>12} {:>12}", "m1", "m2", "m1 speed", "m2 speed");
+    let mut reads: u64 = 0;
+    while count.is_none_or(|count| reads < count) {
+        let (m1, m2) = roboclaw.read_encoders(None)?;
+        let (m1_speed, m2_speed) = roboclaw.read_speeds(None)?;
+        println!("{:>12} {:>12} {:>12} {:>12}", m1, m2, m1_speed, m2_speed);
+        reads += 1;
+        thread::sleep(interval);
+    }
+    Ok(())
+}
+
+fn pid(roboclaw: &mut RoboClaw, command: PidCommand) -> Result<()> {
+    match command {
+        PidCommand::Get { motor } => {
+            let motors: Vec<Motor> = match motor {
+                Some(motor) => vec![motor.into()],
+                None => vec![Motor::M1, Motor::M2],
+            };
+            for motor in motors {
+                println!("{:?} velocity: {:?}", motor, VelocityPid::read(roboclaw, motor)?);
+                println!("{:?} position: {:?}", motor, PositionPid::read(roboclaw, motor)?);
+            }
+        }
+        PidCommand::Velocity {
+            motor,
+            qpps,
+            p,
+            i,
+            d,
+            save,
+        } => {
+            VelocityPid { qpps, p, i, d }.write(roboclaw, motor.into())?;
+            if save {
+                roboclaw.write_settings_to_eeprom(None)?;
+            }
+        }
+        PidCommand::Position {
+            motor,
+            p,
+            i,
+            d,
+            max_integral,
+            deadzone,
+            min_position,
+            max_position,
+            save,
+        } => {
+            let pid: PositionPid = PositionPid {
+                p,
+                i,
+                d,
+                max_integral,
+                deadzone,
+                min_position,
+                max_position,
+            };
+            pid.write(roboclaw, motor.into())?;
+            if save {
+                roboclaw.write_settings_to_eeprom(None)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn config(roboclaw: &mut RoboClaw, command: ConfigCommand) -> Result<()> {
+    match command {
+        ConfigCommand::Dump { file } => {
+            let text: String = toml::to_string_pretty(&Config::read(roboclaw)?)?;
+            match file {
+                Some(file) => std::fs::write(&file, text).with_context(|| format!("couldn't write {}", file.display()))?,
+                None => print!("{}", text),
+            }
+        }
+        ConfigCommand::Apply { file, save } => {
+            let text: String =
+                std::fs::read_to_string(&file).with_context(|| format!("couldn't read {}", file.display()))?;
+            let config: Config = toml::from_str(&text).with_context(|| format!("invalid settings file {}", file.display()))?;
+            config.write(roboclaw)?;
+            if save {
+                roboclaw.write_settings_to_eeprom(None)?;
+            }
+        }
+    }
+    Ok(())
+}
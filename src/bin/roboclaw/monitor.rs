@@ -0,0 +1,397 @@
+//! Live terminal dashboard of `roboclaw monitor`.
+
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use roboclaw_python::roboclaw::{CommStats, Motor, RoboClaw, Status, StatusFlag};
+use std::time::{Duration, Instant};
+
+/// Duty cycle of the jog keys in percent.
+const JOG_STEPS: [f32; 5] = [5.0, 10.0, 25.0, 50.0, 100.0];
+const DUTY_PER_PERCENT: f32 = i16::MAX as f32 / 100.0;
+
+const HELP: &str = "w/s jog M1  i/k jog M2  ↑/↓ jog both  +/- step  space e-stop  r reset counters  q quit";
+
+/// Last values read from the RoboClaw, `None` until the first successful read.
+#[derive(Default)]
+struct Readings {
+    encoders: Option<(i64, i64)>,
+    speeds: Option<(i64, i64)>,
+    pwms: Option<(i16, i16)>,
+    currents: Option<(f32, f32)>,
+    main_battery: Option<f32>,
+    logic_battery: Option<f32>,
+    temperature: Option<f32>,
+    temperature2: Option<f32>,
+    status: Option<Status>,
+}
+
+struct Monitor {
+    address: u8,
+    firmware: String,
+    interval: Duration,
+    readings: Readings,
+    /// Decided by the first read, as only some models have a second sensor. Later failures are reported
+    /// like those of the other reads.
+    has_temperature2: Option<bool>,
+    /// Commanded duty cycles in percent.
+    duty: [f32; 2],
+    step: usize,
+    stopped: bool,
+    last_error: Option<String>,
+    stats: CommStats,
+}
+
+/// Polls the RoboClaw every `interval` and redraws until `q` is pressed. The motors are stopped on exit.
+pub fn run(roboclaw: &mut RoboClaw, address: u8, interval: Duration) -> Result<()> {
+    let mut monitor: Monitor = Monitor {
+        address,
+        firmware: roboclaw.read_version(None).unwrap_or_else(|e| format!("unknown ({})", e)),
+        interval,
+        readings: Readings::default(),
+        has_temperature2: None,
+        duty: [0.0; 2],
+        step: 1,
+        stopped: false,
+        last_error: None,
+        stats: roboclaw.comm_stats(),
+    };
+
+    let mut guard: StopGuard = StopGuard(Some(roboclaw));
+    let mut terminal: DefaultTerminal = ratatui::init();
+    let result: Result<()> = monitor.run(&mut terminal, guard.roboclaw());
+    ratatui::restore();
+
+    result.and(guard.stop())
+}
+
+/// Sends zero duty to both motors when dropped, so they are also stopped if the monitor panics.
+struct StopGuard<'a>(Option<&'a mut RoboClaw>);
+
+impl StopGuard<'_> {
+    fn roboclaw(&mut self) -> &mut RoboClaw {
+        self.0.as_deref_mut().expect("the motors were already stopped")
+    }
+
+    /// Stops the motors now and returns the error instead of ignoring it like a drop does.
+    fn stop(mut self) -> Result<()> {
+        stop_motors(self.roboclaw())?;
+        self.0 = None;
+        Ok(())
+    }
+}
+
+impl Drop for StopGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(roboclaw) = self.0.take() {
+            let _ = stop_motors(roboclaw);
+        }
+    }
+}
+
+/// Sends zero duty to both motors, M2 is stopped even if stopping M1 failed.
+fn stop_motors(roboclaw: &mut RoboClaw) -> Result<()> {
+    let m1: Result<bool> = roboclaw.set_speed_duty(Motor::M1, 0, None);
+    let m2: Result<bool> = roboclaw.set_speed_duty(Motor::M2, 0, None);
+    m1.and(m2).map(|_| ())
+}
+
+impl Monitor {
+    fn run(&mut self, terminal: &mut DefaultTerminal, roboclaw: &mut RoboClaw) -> Result<()> {
+        loop {
+            let started: Instant = Instant::now();
+            self.poll(roboclaw);
+            terminal.draw(|frame| self.draw(frame))?;
+
+            while let Some(timeout) = self.interval.checked_sub(started.elapsed()) {
+                if !event::poll(timeout)? {
+                    break;
+                }
+                if let Event::Key(key) = event::read()?
+                    && key.kind == KeyEventKind::Press
+                    && !self.handle_key(key, roboclaw)
+                {
+                    return Ok(());
+                }
+                terminal.draw(|frame| self.draw(frame))?;
+            }
+        }
+    }
+
+    /// Returns false if the monitor should quit.
+    fn handle_key(&mut self, key: KeyEvent, roboclaw: &mut RoboClaw) -> bool {
+        let step: f32 = JOG_STEPS[self.step];
+        let jog: Option<[f32; 2]> = match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char(' ') => {
+                let result: Result<()> = self.stop(roboclaw);
+                self.report(result);
+                None
+            }
+            KeyCode::Char('w') => Some([step, 0.0]),
+            KeyCode::Char('s') => Some([-step, 0.0]),
+            KeyCode::Char('i') => Some([0.0, step]),
+            KeyCode::Char('k') => Some([0.0, -step]),
+            KeyCode::Up => Some([step, step]),
+            KeyCode::Down => Some([-step, -step]),
+            KeyCode::Char('+') => {
+                self.step = (self.step + 1).min(JOG_STEPS.len() - 1);
+                None
+            }
+            KeyCode::Char('-') => {
+                self.step = self.step.saturating_sub(1);
+                None
+            }
+            KeyCode::Char('r') => {
+                roboclaw.reset_comm_stats();
+                self.last_error = None;
+                None
+            }
+            _ => None,
+        };
+
+        if let Some(change) = jog {
+            for (duty, change) in self.duty.iter_mut().zip(change) {
+                *duty = (*duty + change).clamp(-100.0, 100.0);
+            }
+            self.stopped = false;
+            let result: Result<()> = self.send_duty(roboclaw);
+            self.report(result);
+        }
+        true
+    }
+
+    fn send_duty(&self, roboclaw: &mut RoboClaw) -> Result<()> {
+        for (motor, duty) in [Motor::M1, Motor::M2].into_iter().zip(self.duty) {
+            roboclaw.set_speed_duty(motor, (duty * DUTY_PER_PERCENT).round() as i16, None)?;
+        }
+        Ok(())
+    }
+
+    fn stop(&mut self, roboclaw: &mut RoboClaw) -> Result<()> {
+        self.duty = [0.0; 2];
+        self.stopped = true;
+        stop_motors(roboclaw)
+    }
+
+    fn report(&mut self, result: Result<()>) {
+        if let Err(e) = result {
+            self.last_error = Some(e.to_string());
+        }
+    }
+
+    /// Reads everything shown, a failing read keeps the previous value.
+    fn poll(&mut self, roboclaw: &mut RoboClaw) {
+        fn update<T>(value: &mut Option<T>, read: Result<T>, error: &mut Option<String>) {
+            match read {
+                Ok(read) => *value = Some(read),
+                Err(e) => *error = Some(e.to_string()),
+            }
+        }
+
+        let readings: &mut Readings = &mut self.readings;
+        let error: &mut Option<String> = &mut self.last_error;
+        update(&mut readings.encoders, roboclaw.read_encoders(None), error);
+        update(&mut readings.speeds, roboclaw.read_speeds(None), error);
+        update(&mut readings.pwms, roboclaw.read_pwms(None), error);
+        update(&mut readings.currents, roboclaw.read_currents(None), error);
+        update(&mut readings.main_battery, roboclaw.read_main_battery_voltage(None), error);
+        update(&mut readings.logic_battery, roboclaw.read_logic_battery_voltage(None), error);
+        update(&mut readings.temperature, roboclaw.read_temperature(1, None), error);
+        update(&mut readings.status, roboclaw.read_status(None), error);
+        self.poll_temperature2(roboclaw);
+        self.stats = roboclaw.comm_stats();
+    }
+
+    fn poll_temperature2(&mut self, roboclaw: &mut RoboClaw) {
+        if self.has_temperature2 == Some(false) {
+            return;
+        }
+        match roboclaw.read_temperature(2, None) {
+            Ok(temperature) => {
+                self.readings.temperature2 = Some(temperature);
+                self.has_temperature2 = Some(true);
+            }
+            Err(_) if self.has_temperature2.is_none() => self.has_temperature2 = Some(false),
+            Err(e) => self.last_error = Some(e.to_string()),
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [header, body, footer] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] = Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(body);
+        let [motors, power] = Layout::vertical([Constraint::Length(9), Constraint::Min(0)]).areas(left);
+        let [status, comm] = Layout::vertical([Constraint::Min(0), Constraint::Length(9)]).areas(right);
+
+        frame.render_widget(
+            Line::from(vec![
+                Span::from(format!(" address {} ", self.address)).bold(),
+                Span::from(self.firmware.as_str()),
+            ]),
+            header,
+        );
+        self.draw_motors(frame, motors);
+        self.draw_power(frame, power);
+        self.draw_status(frame, status);
+        self.draw_comm(frame, comm);
+        frame.render_widget(Line::from(HELP).dim(), footer);
+    }
+
+    fn draw_motors(&self, frame: &mut Frame, area: Rect) {
+        let readings: &Readings = &self.readings;
+        let pair = |values: Option<(String, String)>| values.unwrap_or(("-".to_string(), "-".to_string()));
+        let rows: Vec<Row> = vec![
+            ("encoder", pair(readings.encoders.map(|(m1, m2)| (m1.to_string(), m2.to_string())))),
+            ("speed", pair(readings.speeds.map(|(m1, m2)| (format!("{} c/s", m1), format!("{} c/s", m2))))),
+            (
+                "pwm",
+                pair(readings.pwms.map(|(m1, m2)| {
+                    let percent = |pwm: i16| format!("{:.1} %", pwm as f32 / DUTY_PER_PERCENT);
+                    (percent(m1), percent(m2))
+                })),
+            ),
+            ("current", pair(readings.currents.map(|(m1, m2)| (format!("{:.2} A", m1), format!("{:.2} A", m2))))),
+            ("jog duty", (format!("{:.0} %", self.duty[0]), format!("{:.0} %", self.duty[1]))),
+        ]
+        .into_iter()
+        .map(|(name, (m1, m2))| Row::new(vec![name.to_string(), m1, m2]))
+        .collect();
+
+        let title: Line = if self.stopped {
+            Line::from(" Motors (stopped) ").red().bold()
+        } else {
+            Line::from(format!(" Motors (jog step {} %) ", JOG_STEPS[self.step]))
+        };
+        let table: Table = Table::new(rows, [Constraint::Length(10), Constraint::Fill(1), Constraint::Fill(1)])
+            .header(Row::new(vec!["", "M1", "M2"]).add_modifier(Modifier::BOLD))
+            .block(Block::bordered().title(title));
+        frame.render_widget(table, area);
+    }
+
+    fn draw_power(&self, frame: &mut Frame, area: Rect) {
+        let readings: &Readings = &self.readings;
+        let value = |value: Option<f32>, unit: &str| value.map_or("-".to_string(), |value| format!("{:.1} {}", value, unit));
+        let temperature2: String = if self.has_temperature2 != Some(false) {
+            value(readings.temperature2, "°C")
+        } else {
+            "n/a".to_string()
+        };
+        let lines: Vec<Line> = vec![
+            Line::from(format!("main battery   {}", value(readings.main_battery, "V"))),
+            Line::from(format!("logic battery  {}", value(readings.logic_battery, "V"))),
+            Line::from(format!("temperature    {}", value(readings.temperature, "°C"))),
+            Line::from(format!("temperature 2  {}", temperature2)),
+        ];
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(" Power ")), area);
+    }
+
+    fn draw_status(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = match self.readings.status {
+            None => vec![Line::from("-")],
+            Some(status) if status.normal() => vec![Line::from("normal").green()],
+            Some(status) => status
+                .flags()
+                .into_iter()
+                .map(|flag| Line::from(format!("{:?}", flag)).style(flag_style(flag)))
+                .collect(),
+        };
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(" Status ")), area);
+    }
+
+    fn draw_comm(&self, frame: &mut Frame, area: Rect) {
+        let stats: CommStats = self.stats;
+        let counter = |name: &str, count: u64| {
+            let line: Line = Line::from(format!("{:<12}{}", name, count));
+            if count > 0 { line.yellow() } else { line }
+        };
+        let mut lines: Vec<Line> = vec![
+            Line::from(format!("{:<12}{}", "packets", stats.packets)),
+            counter("timeouts", stats.timeouts),
+            counter("crc errors", stats.crc_errors),
+            counter("nacks", stats.nacks),
+            counter("io errors", stats.io_errors),
+        ];
+        if let Some(error) = &self.last_error {
+            lines.push(Line::from(format!("last: {}", error)).red());
+        }
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(" Communication ")), area);
+    }
+}
+
+fn flag_style(flag: StatusFlag) -> Style {
+    let name: String = format!("{:?}", flag);
+    if flag == StatusFlag::EStop || name.ends_with("Error") {
+        Style::new().fg(Color::Red).add_modifier(Modifier::BOLD)
+    } else if name.ends_with("Warning") {
+        Style::new().fg(Color::Yellow)
+    } else {
+        Style::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roboclaw_python::roboclaw::testing::{acked, answered, fake, unanswered};
+    use roboclaw_python::roboclaw::CaptureEvent;
+    use std::panic::{self, AssertUnwindSafe};
+
+    fn monitor() -> Monitor {
+        Monitor {
+            address: 0x80,
+            firmware: String::new(),
+            interval: Duration::from_millis(100),
+            readings: Readings::default(),
+            has_temperature2: None,
+            duty: [0.0; 2],
+            step: 1,
+            stopped: false,
+            last_error: None,
+            stats: CommStats::default(),
+        }
+    }
+
+    fn read_temperature2(answer: Option<u16>) -> Vec<CaptureEvent> {
+        match answer {
+            Some(answer) => answered(vec![0x80, 83], answer.to_be_bytes().to_vec()),
+            None => unanswered(vec![0x80, 83]),
+        }
+    }
+
+    #[test]
+    fn stops_motors_on_panic() {
+        let mut roboclaw: RoboClaw = fake(vec![acked(vec![0x80, 32, 0, 0]), acked(vec![0x80, 33, 0, 0])]);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let _guard: StopGuard = StopGuard(Some(&mut roboclaw));
+            panic!("monitor crashed");
+        }));
+        assert!(result.is_err());
+        assert_eq!(roboclaw.comm_stats().packets, 2);
+        assert_eq!(roboclaw.comm_stats().errors(), 0);
+    }
+
+    #[test]
+    fn temperature2() {
+        let mut roboclaw: RoboClaw = fake(vec![read_temperature2(Some(253)), read_temperature2(None), read_temperature2(Some(254))]);
+        let mut present: Monitor = monitor();
+        present.poll_temperature2(&mut roboclaw);
+        present.poll_temperature2(&mut roboclaw);
+        assert_eq!(present.has_temperature2, Some(true));
+        assert!(present.last_error.is_some());
+        present.poll_temperature2(&mut roboclaw);
+        assert_eq!(present.readings.temperature2, Some(25.4));
+
+        let mut roboclaw: RoboClaw = fake(vec![read_temperature2(None)]);
+        let mut missing: Monitor = monitor();
+        missing.poll_temperature2(&mut roboclaw);
+        missing.poll_temperature2(&mut roboclaw);
+        assert_eq!(missing.has_temperature2, Some(false));
+        assert!(missing.last_error.is_none());
+    }
+}
@@ -1,19 +1,51 @@
 #[allow(unused_imports)]
 
-mod roboclaw;
+pub mod roboclaw;
 use pyo3::prelude::*;
-use roboclaw::{RoboClaw, Motor};
+use roboclaw::{RoboClaw, Motor, Direction, EncoderMode, Model, PinMode, PwmMode, CtrlMode, Ctrl, Defaults, ControlMode, BatteryMode, StandardConfig, Status, StatusFlag, ErrorKind, ErrorEvent, ErrorSupervisor, CommStats, DifferentialDrive, Odometry, OdometryEstimate, MotorUnits, Unit, Segment, TrajectoryPlanner, Channel, TelemetryPoller, RecordFormat, TelemetryRecorder, trace_packets, analyze_lines};
 
 #[pymodule]
 fn roboclaw_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<RoboClaw>()?;
     m.add_class::<Motor>()?;
+    m.add_class::<Direction>()?;
+    m.add_class::<EncoderMode>()?;
+    m.add_class::<Model>()?;
+    m.add_class::<PinMode>()?;
+    m.add_class::<PwmMode>()?;
+    m.add_class::<CtrlMode>()?;
+    m.add_class::<Ctrl>()?;
+    m.add_class::<Defaults>()?;
+    m.add_class::<ControlMode>()?;
+    m.add_class::<BatteryMode>()?;
+    m.add_class::<StandardConfig>()?;
+    m.add_class::<Status>()?;
+    m.add_class::<StatusFlag>()?;
+    m.add_class::<ErrorKind>()?;
+    m.add_class::<ErrorEvent>()?;
+    m.add_class::<ErrorSupervisor>()?;
+    m.add_class::<CommStats>()?;
+    m.add_class::<DifferentialDrive>()?;
+    m.add_class::<Odometry>()?;
+    m.add_class::<OdometryEstimate>()?;
+    m.add_class::<MotorUnits>()?;
+    m.add_class::<Unit>()?;
+    m.add_class::<Segment>()?;
+    m.add_class::<TrajectoryPlanner>()?;
+    m.add_class::<Channel>()?;
+    m.add_class::<TelemetryPoller>()?;
+    m.add_class::<RecordFormat>()?;
+    m.add_class::<TelemetryRecorder>()?;
+    m.add_function(wrap_pyfunction!(trace_packets, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze_lines, m)?)?;
     Ok(())
 } 
 
 #[cfg(test)]
 mod tests {
+    use crate::roboclaw::testing::*;
     use crate::roboclaw::*;
+    use pyo3::Python;
 
     #[test]
     fn crc() {
@@ -38,4 +70,371 @@ mod tests {
         println!("{}", encoder_value);  
         assert!(true);
     }
+
+    #[test]
+    fn unwrap() {
+        assert_eq!(unwrap_encoder(0, 100), 100);
+        assert_eq!(unwrap_encoder(100, u32::MAX), -1);
+        assert_eq!(unwrap_encoder(u32::MAX as i64, 5), u32::MAX as i64 + 6);
+        assert_eq!(unwrap_encoder(-5, 10), 10);
+    }
+
+    #[test]
+    fn kinematics() {
+        let kinematics: Kinematics = Kinematics::new(0.05, 0.5, 1000.0, 2.0).unwrap();
+
+        assert_eq!(kinematics.wheel_velocities(1.0, 2.0), (0.5, 1.5));
+        assert_eq!(kinematics.body_velocity(0.5, 1.5), (1.0, 2.0));
+        assert!((kinematics.meters_to_counts(2.0 * std::f64::consts::PI * 0.05) - 2000.0).abs() < 1e-9);
+        assert!(Kinematics::new(0.0, 0.5, 1000.0, 2.0).is_err());
+    }
+
+    #[test]
+    fn odometry() {
+        let wheel_radius: f64 = 0.5 / std::f64::consts::PI;
+        let mut odometry: Odometry = Odometry::new(wheel_radius, 1.0, 100.0, 1.0, Motor::M1, [0.0; 3], [0.0; 2]).unwrap();
+        let counts_per_meter: i64 = 100;
+
+        odometry.update(0, 0, Some(0.0));
+        let estimate: OdometryEstimate = odometry.update(counts_per_meter, counts_per_meter, Some(1.0));
+        assert!((estimate.x - 1.0).abs() < 0.01);
+        assert!(estimate.y.abs() < 1e-9);
+        assert!((estimate.linear - 1.0).abs() < 0.01);
+
+        assert!(odometry.update_motor(Motor::M1, counts_per_meter, Some(2.0)).is_none());
+        let estimate: OdometryEstimate = odometry.update_motor(Motor::M2, 2 * counts_per_meter, Some(2.0)).unwrap();
+        assert!((estimate.heading - 1.0).abs() < 0.01);
+        assert!((estimate.angular - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn units() {
+        let units: MotorUnits = MotorUnits::new(500.0, 4.0, Some(0.1)).unwrap();
+
+        assert_eq!(units.convert_to_counts(1.0, Unit::Revolutions).unwrap(), 2000.0);
+        assert!((units.convert_speed_to_counts(60.0, Unit::Revolutions).unwrap() - 2000.0).abs() < 1e-9);
+        assert!((units.convert_from_counts(1000.0, Unit::Radians).unwrap() - std::f64::consts::PI).abs() < 1e-9);
+        assert!((units.convert_to_counts(0.1 * std::f64::consts::PI, Unit::Meters).unwrap() - 2000.0).abs() < 1e-9);
+        assert!(MotorUnits::COUNTS.convert_to_counts(1.0, Unit::Meters).is_err());
+    }
+
+    #[test]
+    fn trajectory() {
+        let trapezoid: TrajectoryPlanner = TrajectoryPlanner::new(1000.0, 500.0, None, 8).unwrap();
+        let segments: Vec<Segment> = trapezoid.plan_move(5000.0).unwrap();
+        assert_eq!(segments, vec![
+            Segment::new(1000, 500, 1000),
+            Segment::new(1000, 500, 3000),
+            Segment::new(0, 500, 1000),
+        ]);
+
+        let segments: Vec<Segment> = trapezoid.plan_move(-500.0).unwrap();
+        assert_eq!(segments.iter().map(|segment| segment.distance).sum::<u32>(), 500);
+        assert!(segments.iter().all(|segment| segment.speed <= 0));
+
+        let s_curve: TrajectoryPlanner = TrajectoryPlanner::new(1000.0, 500.0, Some(1000.0), 4).unwrap();
+        let segments: Vec<Segment> = s_curve.plan(vec![3000.0, 6000.0], Some(vec![500.0, 0.0]), 0.0).unwrap();
+        assert_eq!(segments.iter().map(|segment| segment.distance).sum::<u32>(), 6000);
+        assert!(segments.iter().all(|segment| segment.accel <= 500));
+        assert_eq!(segments.last().unwrap().speed, 0);
+
+        assert!(trapezoid.plan(vec![100.0], Some(vec![100.0]), 0.0).is_err());
+    }
+
+    #[test]
+    fn ring_buffer() {
+        let buffer: RingBuffer = RingBuffer::new(3).unwrap();
+        for index in 0..5 {
+            buffer.push(Sample { timestamp: index as f64, channel: Channel::Encoders, values: [index as f64, 0.0] });
+        }
+
+        let timestamps = |samples: Vec<Sample>| samples.iter().map(|sample| sample.timestamp).collect::<Vec<f64>>();
+        assert_eq!(timestamps(buffer.snapshot()), vec![2.0, 3.0, 4.0]);
+        assert_eq!(timestamps(buffer.drain()), vec![2.0, 3.0, 4.0]);
+        assert!(buffer.drain().is_empty());
+
+        buffer.push(Sample { timestamp: 5.0, channel: Channel::Status, values: [1.0, f64::NAN] });
+        assert_eq!(timestamps(buffer.drain()), vec![5.0]);
+        assert_eq!(buffer.snapshot().len(), 3);
+        assert_eq!(buffer.written(), 6);
+    }
+
+    #[test]
+    fn poller_rates() {
+        for rate in [0.0, -1.0, f64::NAN, 1e-320] {
+            let rates: std::collections::HashMap<Channel, f64> = [(Channel::Encoders, rate)].into();
+            assert!(TelemetryPoller::new("/dev/null", 38400, 10, 1, 0x80, rates, 16).is_err());
+        }
+    }
+
+    #[test]
+    fn recorder() {
+        let samples: Vec<Sample> = vec![
+            Sample { timestamp: 1.5, channel: Channel::Encoders, values: [100.0, -20.0] },
+            Sample { timestamp: 2.0, channel: Channel::MainBattery, values: [12.3, f64::NAN] },
+        ];
+        let path: std::path::PathBuf = std::env::temp_dir().join(format!("roboclaw-recorder-{}.rctl", std::process::id()));
+
+        let mut writer: RecordWriter = RecordWriter::create(&path, RecordFormat::Columnar).unwrap();
+        writer.write(&samples).unwrap();
+        writer.write(&samples[..1]).unwrap();
+        writer.flush().unwrap();
+
+        let read: Vec<Sample> = read_columnar(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read.len(), 3);
+        assert_eq!(read[0], samples[0]);
+        assert_eq!(read[1].channel, Channel::MainBattery);
+        assert!(read[1].values[1].is_nan());
+        assert_eq!(read[2], samples[0]);
+    }
+
+    #[test]
+    fn replay() {
+        let mut roboclaw: RoboClaw = fake(vec![
+            acked(vec![0x80, 37, 0, 0, 0, 100, 0xFF, 0xFF, 0xFF, 0x9C]),
+            answered(vec![0x80, 78], vec![0, 0, 1, 0xF4, 0xFF, 0xFF, 0xFF, 0xF6]),
+        ]);
+
+        assert!(roboclaw.drive_speed(100, -100, None).unwrap());
+        assert_eq!(roboclaw.read_encoders(None).unwrap(), (500, -10));
+        assert!(roboclaw.drive_speed(0, 0, None).is_err());
+
+        let events: Vec<CaptureEvent> = parse_capture("# roboclaw capture 1\n0.0 CLEAR\n0.1 TX 80 4E\n0.2 TX 00\n0.3 TIMEOUT\n").unwrap();
+        assert_eq!(events, vec![CaptureEvent::Clear, CaptureEvent::Tx(vec![0x80, 0x4E, 0x00]), CaptureEvent::Timeout]);
+        assert!(parse_capture("0.0 TX 8G").is_err());
+    }
+
+    #[test]
+    fn fixed_width_writes() {
+        let mut roboclaw: RoboClaw = fake(vec![
+            acked(vec![0x80, 32, 0xFF, 0x9C]),
+            acked(vec![0x80, 34, 0, 5, 0, 5]),
+            acked(vec![0x80, 28, 0, 0, 0x40, 0, 0, 1, 0, 0, 0, 0, 0x80, 0, 0, 0, 0xAB, 0xE0]),
+            acked(vec![0x80, 22, 0, 0, 0, 0]),
+        ]);
+
+        assert!(roboclaw.set_speed_duty(Motor::M1, -100, None).unwrap());
+        assert!(roboclaw.drive_duty(5, None).unwrap());
+        assert!(roboclaw.set_velocity_pid(Motor::M1, 44000, 0x10000, 0x8000, 0x4000, None).unwrap());
+        assert!(roboclaw.set_encoder(Motor::M1, 0, None).unwrap());
+    }
+
+    #[test]
+    fn max_current_keeps_min_current() {
+        let mut roboclaw: RoboClaw = fake(vec![
+            answered(vec![0x80, 135], vec![0, 0, 0x05, 0xDC, 0, 0, 0, 0x32]),
+            acked(vec![0x80, 133, 0, 0, 0x03, 0xE8, 0, 0, 0, 0x32]),
+            acked(vec![0x80, 134, 0, 0, 0x03, 0xE8, 0, 0, 0, 0x64]),
+        ]);
+
+        assert!(roboclaw.set_max_current(Motor::M1, 10.0, None, None).unwrap());
+        assert!(roboclaw.set_max_current(Motor::M2, 10.0, Some(1.0), None).unwrap());
+        assert!(roboclaw.set_max_current(Motor::M2, 10.0, Some(11.0), None).is_err());
+    }
+
+    #[test]
+    fn home() {
+        let mut roboclaw: RoboClaw = fake(vec![
+            acked(vec![0x80, 32, 0xFC, 0x18]),
+            answered(vec![0x80, 90], vec![0, 0, 0, 0]),
+            answered(vec![0x80, 90], vec![0, 0x40, 0, 0]),
+            acked(vec![0x80, 32, 0, 0]),
+            acked(vec![0x80, 22, 0, 0, 0, 0]),
+        ]);
+
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            assert!(roboclaw.home(py, Motor::M1, Direction::Backward, 1000, 1.0, None).unwrap());
+            assert!(roboclaw.home(py, Motor::M1, Direction::Forward, 1000, -1.0, None).is_err());
+            assert!(roboclaw.home(py, Motor::M1, Direction::Forward, 1000, f64::NAN, None).is_err());
+        });
+    }
+
+    #[test]
+    fn supervisor_fraction() {
+        let mut roboclaw: RoboClaw = fake(vec![]);
+
+        for fraction in [0.0, -0.5, 1.5, f64::NAN] {
+            assert!(ErrorSupervisor::from_controller(&mut roboclaw, fraction, 0.1, None).is_err());
+        }
+    }
+
+    #[test]
+    fn interleaved_encoder_reads() {
+        let mut roboclaw: RoboClaw = fake(vec![
+            answered(vec![0x80, 16], vec![0, 0, 0x03, 0xE8, 0]),
+            answered(vec![0x80, 78], vec![0, 0, 0x03, 0xE8, 0xFF, 0xFF, 0xFF, 0xFB]),
+            answered(vec![0x80, 16], vec![0, 0, 0x03, 0xE8, 0]),
+            answered(vec![0x80, 17], vec![0xFF, 0xFF, 0xFF, 0xFB, 0]),
+            answered(vec![0x80, 78], vec![0, 0, 0x04, 0x00, 0xFF, 0xFF, 0xFF, 0xFA]),
+        ]);
+
+        assert_eq!(roboclaw.read_encoder(Motor::M1, None).unwrap(), 1000);
+        assert_eq!(roboclaw.read_encoders(None).unwrap(), (1000, -5));
+        assert_eq!(roboclaw.read_encoder(Motor::M1, None).unwrap(), 1000);
+        assert_eq!(roboclaw.read_encoder(Motor::M2, None).unwrap(), -5);
+        assert_eq!(roboclaw.read_encoders(None).unwrap(), (1024, -6));
+    }
+
+    #[test]
+    fn encoder_reset_after_wrap() {
+        let counts = |m1: u32| answered(vec![0x80, 78], [m1.to_be_bytes(), 5u32.to_be_bytes()].concat());
+        let mut roboclaw: RoboClaw = fake(vec![
+            counts(0x4000_0000),
+            counts(0x8000_0000),
+            counts(0xC000_0000),
+            counts(0),
+            counts(0x4000_0000),
+            acked(vec![0x80, 20]),
+            counts(0),
+            counts(0x4000_0000),
+            counts(0x8000_0000),
+            counts(0xC000_0000),
+            acked(vec![0x80, 22, 0, 0, 0, 7]),
+            answered(vec![0x80, 16], vec![0, 0, 0, 7, 0]),
+        ]);
+
+        for _ in 0..4 {
+            roboclaw.read_encoders(None).unwrap();
+        }
+        assert_eq!(roboclaw.read_encoders(None).unwrap(), (5_368_709_120, 5));
+        assert!(roboclaw.reset_encoders(None).unwrap());
+        assert_eq!(roboclaw.read_encoders(None).unwrap(), (0, 5));
+
+        roboclaw.read_encoders(None).unwrap();
+        roboclaw.read_encoders(None).unwrap();
+        assert_eq!(roboclaw.read_encoders(None).unwrap(), (3_221_225_472, 5));
+        assert!(roboclaw.set_encoder(Motor::M1, 7, None).unwrap());
+        assert_eq!(roboclaw.read_encoder(Motor::M1, None).unwrap(), 7);
+    }
+
+    #[test]
+    fn position() {
+        let mut roboclaw: RoboClaw = fake(vec![
+            answered(vec![0x80, 16], vec![0, 0, 0x03, 0xE8, 0]),
+            answered(vec![0x80, 16], vec![0, 0, 0x03, 0xE8, 0]),
+            answered(vec![0x80, 16], vec![0x80, 0, 0, 0, 0]),
+            answered(vec![0x80, 16], vec![0xC0, 0, 0, 0, 0]),
+            acked(vec![0x80, 22, 0, 0, 0, 0]),
+            answered(vec![0x80, 16], vec![0, 0, 0, 0, 0]),
+        ]);
+
+        assert_eq!(roboclaw.read_position(Motor::M1, Unit::Counts, None).unwrap(), 1000.0);
+        assert_eq!(roboclaw.read_position(Motor::M1, Unit::Counts, None).unwrap(), 1000.0);
+        roboclaw.read_position(Motor::M1, Unit::Counts, None).unwrap();
+        assert_eq!(roboclaw.read_position(Motor::M1, Unit::Counts, None).unwrap(), 3221225472.0);
+        // the accumulator has to follow, otherwise 0 is unwrapped to the next wrap at 2^32
+        assert!(roboclaw.set_position(Motor::M1, 0.0, Unit::Counts, None).unwrap());
+        assert_eq!(roboclaw.read_position(Motor::M1, Unit::Counts, None).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn trajectory_stops_on_error() {
+        let segment: Vec<u8> = vec![0, 0, 0x01, 0xF4, 0, 0, 0x03, 0xE8, 0, 0, 0x07, 0xD0];
+        let mut roboclaw: RoboClaw = fake(vec![
+            answered(vec![0x80, 47], vec![0x80, 0x80]),
+            acked([vec![0x80, 44], segment.clone(), vec![1]].concat()),
+            nacked([vec![0x80, 44], segment, vec![0]].concat()),
+            acked(vec![0x80, 34, 0, 0, 0, 0]),
+        ]);
+
+        pyo3::prepare_freethreaded_python();
+        let segments: Vec<Segment> = vec![Segment::new(1000, 500, 2000); 2];
+        Python::with_gil(|py| {
+            assert!(roboclaw.execute_trajectory(py, segments, Some(Motor::M1), 8, 0.0, true, None).is_err());
+        });
+        assert_eq!(roboclaw.comm_stats().packets, 4);
+        assert_eq!(roboclaw.comm_stats().nacks, 1);
+    }
+
+    #[test]
+    fn read_version() {
+        let mut roboclaw: RoboClaw = fake(vec![
+            answered(vec![0x80, 21], b"USB Roboclaw 2x15a v4.2.8\n\0".to_vec()),
+            acked(vec![0x80, 32, 0xFF, 0x9C]),
+        ]);
+
+        assert_eq!(roboclaw.read_version(None).unwrap(), "USB Roboclaw 2x15a v4.2.8");
+        assert!(roboclaw.set_speed_duty(Motor::M1, -100, None).unwrap());
+        assert_eq!(roboclaw.comm_stats().packets, 2);
+        assert_eq!(roboclaw.comm_stats().errors(), 0);
+    }
+
+    #[test]
+    fn analyzer() {
+        let drive: Vec<u8> = with_crc(vec![0x80, 32, 0x10, 0x00]);
+        let battery: Vec<u8> = with_crc(vec![0x81, 24, 0x00, 0x7B]);
+        let stream: Vec<u8> = [
+            vec![0x12, 0x34],
+            drive.clone(),
+            vec![0xFF],
+            battery.clone(),
+            drive.clone(),
+            vec![0x80, 24],
+        ]
+        .concat();
+
+        let frames: Vec<Frame> = analyze(&stream);
+        assert_eq!(frames.len(), 5);
+        assert!(matches!(&frames[0], Frame::Malformed { offset: 0, bytes, .. } if bytes == &vec![0x12, 0x34]));
+        assert!(matches!(&frames[1], Frame::Transaction { address: 0x80, command: Commands::M1DriveSignedDutyCycle, response, .. } if response == &vec![0xFF]));
+        assert!(matches!(&frames[2], Frame::Transaction { address: 0x81, command: Commands::ReadMainBatVoltage, response, .. } if response == &vec![0x00, 0x7B]));
+        assert!(matches!(&frames[3], Frame::Orphaned { command: Commands::M1DriveSignedDutyCycle, .. }));
+        assert!(matches!(&frames[4], Frame::Orphaned { command: Commands::ReadMainBatVoltage, .. }));
+
+        let mut corrupted: Vec<u8> = drive.clone();
+        corrupted[2] ^= 1;
+        assert!(matches!(&analyze(&corrupted)[0], Frame::Malformed { reason, .. } if reason.starts_with("crc mismatch")));
+    }
+
+    #[test]
+    fn user_memory() {
+        let mut memory: UserMemory = UserMemory::default();
+        memory.entries.insert("robot_id".to_string(), UserValue::Int(7));
+        memory.entries.insert("wheel".to_string(), UserValue::Str("front_left".to_string()));
+        memory.entries.insert("calibration".to_string(), UserValue::Float(1.5));
+
+        let mut words: Vec<u16> = memory.encode().unwrap();
+        assert_eq!(UserMemory::decode(&words).unwrap(), memory);
+
+        words[3] ^= 1;
+        assert!(UserMemory::decode(&words).is_err());
+    }
+
+    #[test]
+    fn encoder_mode() {
+        let flag = |absolute: bool, reverse_motor: bool, reverse_encoder: bool, rc_analog: bool| EncoderMode {
+            absolute,
+            reverse_motor,
+            reverse_encoder,
+            rc_analog,
+        };
+        assert_eq!(flag(true, false, false, false).bits(), 0x01);
+        assert_eq!(flag(false, true, false, false).bits(), 0x20);
+        assert_eq!(flag(false, false, true, false).bits(), 0x40);
+        assert_eq!(flag(false, false, false, true).bits(), 0x80);
+        assert_eq!(EncoderMode::from_bits(0x41), flag(true, false, true, false));
+        // the bits in between aren't flags and are dropped
+        assert_eq!(EncoderMode::from_bits(0x1E), EncoderMode::default());
+
+        for bits in [0x00, 0x01, 0x20, 0x40, 0x80, 0xE1] {
+            assert_eq!(EncoderMode::from_bits(bits).bits(), bits);
+        }
+    }
+
+    #[test]
+    fn standard_config() {
+        let mut config: StandardConfig = StandardConfig::new(0x2003);
+        assert_eq!(config.mode(), ControlMode::PacketSerial);
+        assert_eq!(config.baud_rate(), 2400);
+        assert_eq!(config.packet_address(), 0x80);
+
+        config.set_baud_rate(115200).unwrap();
+        config.set_packet_address(0x82).unwrap();
+        config.set_battery_mode(BatteryMode::Cell3);
+        assert_eq!(config.bits, 0x22AF);
+        assert!(config.set_baud_rate(1000).is_err());
+        assert!(config.set_packet_address(0x88).is_err());
+    }
 }
@@ -1,9 +1,42 @@
+mod analyzer;
 mod commands;
 mod common;
+mod config;
 mod connection;
 mod crc16;
+mod kinematics;
+mod odometry;
+mod recorder;
 mod roboclaw;
+mod status;
+mod supervisor;
+mod telemetry;
+#[cfg(any(test, feature = "cli"))]
+pub mod testing;
+mod trace;
+mod trajectory;
+mod transport;
+mod units;
+mod user_memory;
 
-pub use common::calculate_encoder;
+pub use analyzer::{analyze, analyze_lines, Frame, ADDRESSES};
+pub use commands::{Commands, Layout};
+pub use common::{calculate_encoder, unwrap_encoder};
+pub use connection::{CommStats, Connection};
+pub use config::{
+    BatteryMode, ControlMode, Ctrl, CtrlMode, Defaults, EncoderMode, Model, PinMode, PwmMode,
+    StandardConfig,
+};
 pub use crc16::Crc16;
-pub use roboclaw::{Motor, RoboClaw};
+pub use kinematics::{DifferentialDrive, Kinematics};
+pub use odometry::{Odometry, OdometryEstimate};
+pub use recorder::{read_columnar, RecordFormat, RecordWriter, TelemetryRecorder};
+pub use roboclaw::{Direction, Motor, RoboClaw};
+pub use status::{Status, StatusFlag};
+pub use supervisor::{ErrorEvent, ErrorKind, ErrorSupervisor};
+pub use telemetry::{Channel, RingBuffer, Sample, TelemetryPoller};
+pub use trace::trace_packets;
+pub use trajectory::{Segment, TrajectoryPlanner};
+pub use transport::{parse_capture, CaptureEvent, RecordingTransport, ReplayTransport, Transport};
+pub use units::{MotorUnits, Unit};
+pub use user_memory::{UserMemory, UserValue};
@@ -0,0 +1,217 @@
+use super::commands::{Commands, Layout};
+use super::common::hex;
+use super::Crc16;
+use pyo3::prelude::*;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// Addresses a RoboClaw can have in packet serial mode.
+pub const ADDRESSES: RangeInclusive<u8> = 0x80..=0x87;
+const ACK: u8 = 0xFF;
+const MAX_STRING: usize = 48;
+
+/// A decoded part of a sniffed byte stream, `offset` is the index of its first byte.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame {
+    /// A request and the matching answer, the acknowledgement of writes or the data of reads.
+    Transaction {
+        offset: usize,
+        address: u8,
+        command: Commands,
+        request: Vec<u8>,
+        response: Vec<u8>,
+    },
+    /// A valid request without an answer.
+    Orphaned {
+        offset: usize,
+        address: u8,
+        command: Commands,
+        request: Vec<u8>,
+        reason: String,
+    },
+    /// Bytes that aren't part of any valid frame.
+    Malformed {
+        offset: usize,
+        bytes: Vec<u8>,
+        reason: String,
+    },
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Frame::Transaction {
+                offset,
+                address,
+                command,
+                request,
+                response,
+            } => write!(
+                f,
+                "{:>8}  0x{:02X} {:?}({}) TX [{}] RX [{}]",
+                offset,
+                address,
+                command,
+                *command as u8,
+                hex(request),
+                hex(response)
+            ),
+            Frame::Orphaned {
+                offset,
+                address,
+                command,
+                request,
+                reason,
+            } => write!(
+                f,
+                "{:>8}  0x{:02X} {:?}({}) TX [{}] ORPHANED: {}",
+                offset,
+                address,
+                command,
+                *command as u8,
+                hex(request),
+                reason
+            ),
+            Frame::Malformed { offset, bytes, reason } => {
+                write!(f, "{:>8}  MALFORMED [{}]: {}", offset, hex(bytes), reason)
+            }
+        }
+    }
+}
+
+/// Decodes a sniffed packet serial byte stream with requests and answers interleaved.
+/// The decoder re-synchronizes on address bytes, bytes that can't be decoded are reported as `Frame::Malformed`.
+pub fn analyze(bytes: &[u8]) -> Vec<Frame> {
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut junk: Option<(usize, Option<String>)> = None;
+    let mut offset: usize = 0;
+
+    while offset < bytes.len() {
+        match decode(bytes, offset) {
+            Ok((frame, length)) => {
+                if let Some((start, reason)) = junk.take() {
+                    frames.push(malformed(bytes, start, offset, reason));
+                }
+                frames.push(frame);
+                offset += length;
+            }
+            Err(reason) => {
+                let (_, first_reason) = junk.get_or_insert((offset, None));
+                if first_reason.is_none() {
+                    *first_reason = reason;
+                }
+                offset += 1;
+            }
+        }
+    }
+    if let Some((start, reason)) = junk {
+        frames.push(malformed(bytes, start, bytes.len(), reason));
+    }
+
+    frames
+}
+
+/// Formats every frame of `analyze` as one line.
+#[pyfunction]
+#[pyo3(name = "analyze")]
+pub fn analyze_lines(data: &[u8]) -> Vec<String> {
+    analyze(data).iter().map(|frame| frame.to_string()).collect()
+}
+
+fn malformed(bytes: &[u8], start: usize, end: usize, reason: Option<String>) -> Frame {
+    Frame::Malformed {
+        offset: start,
+        bytes: bytes[start..end].to_vec(),
+        reason: reason.unwrap_or_else(|| "no address byte".to_string()),
+    }
+}
+
+/// Decodes the frame starting at `offset` and returns it with its length in bytes.
+/// Fails with a reason if the bytes look like a request but don't form a valid frame.
+fn decode(bytes: &[u8], offset: usize) -> Result<(Frame, usize), Option<String>> {
+    let rest: &[u8] = &bytes[offset..];
+    let (&address, &opcode) = match rest {
+        [address, opcode, ..] if ADDRESSES.contains(address) => (address, opcode),
+        _ => return Err(None),
+    };
+    let command: Commands = Commands::from_byte(opcode).ok_or_else(|| Some(format!("unknown command {}", opcode)))?;
+    let layout: Layout = command
+        .layout()
+        .ok_or_else(|| Some(format!("unknown layout of {:?}({})", command, opcode)))?;
+
+    let orphaned = |request: &[u8], length: usize, reason: &str| {
+        Ok((
+            Frame::Orphaned {
+                offset,
+                address,
+                command,
+                request: request.to_vec(),
+                reason: reason.to_string(),
+            },
+            length,
+        ))
+    };
+    let transaction = |request: &[u8], response: &[u8], length: usize| {
+        Ok((
+            Frame::Transaction {
+                offset,
+                address,
+                command,
+                request: request.to_vec(),
+                response: response.to_vec(),
+            },
+            length,
+        ))
+    };
+
+    match layout {
+        Layout::Write(size) => {
+            let end: usize = 2 + size;
+            if rest.len() < end + 2 {
+                return Err(Some(format!("truncated {:?}({})", command, opcode)));
+            }
+            if !crc_matches(&rest[..end], &rest[end..end + 2]) {
+                return Err(Some(format!("crc mismatch in {:?}({})", command, opcode)));
+            }
+
+            let request: &[u8] = &rest[2..end];
+            match rest.get(end + 2) {
+                Some(&ACK) => transaction(request, &[ACK], end + 3),
+                Some(_) => orphaned(request, end + 2, "not acknowledged"),
+                None => orphaned(request, end + 2, "stream ended before the acknowledgement"),
+            }
+        }
+        Layout::Read { request: size, response } => {
+            let end: usize = 2 + size;
+            if rest.len() < end {
+                return Err(Some(format!("truncated {:?}({})", command, opcode)));
+            }
+
+            let request: &[u8] = &rest[2..end];
+            let answer_end: usize = end + response;
+            if rest.len() < answer_end + 2 {
+                return orphaned(request, end, "stream ended before the answer");
+            }
+            if !crc_matches(&rest[..answer_end], &rest[answer_end..answer_end + 2]) {
+                return orphaned(request, end, "missing answer or crc mismatch");
+            }
+            transaction(request, &rest[end..answer_end], answer_end + 2)
+        }
+        Layout::ReadString => {
+            let Some(zero) = rest[2..].iter().take(MAX_STRING).position(|byte| *byte == 0) else {
+                return orphaned(&[], 2, "missing answer or unterminated string");
+            };
+            let answer_end: usize = 2 + zero + 1;
+            if rest.len() < answer_end + 2 || !crc_matches(&rest[..answer_end], &rest[answer_end..answer_end + 2]) {
+                return orphaned(&[], 2, "missing answer or crc mismatch");
+            }
+            transaction(&[], &rest[2..answer_end], answer_end + 2)
+        }
+    }
+}
+
+fn crc_matches(data: &[u8], crc: &[u8]) -> bool {
+    let mut checksum: Crc16 = Crc16::new();
+    checksum.update_bytes(data);
+    checksum.get().to_be_bytes() == crc
+}
@@ -1,5 +1,5 @@
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Commands {
     M1Forward = 0,
     M1Backward = 1,
@@ -131,4 +131,184 @@ pub enum Commands {
 
     EEPROMReadUserMemoryLocation = 252,
     EEPROMWriteUserMemoryLocation = 253,
-}
\ No newline at end of file
+}
+
+/// Bytes following the address and command byte, as described in the packet serial section of the user manual.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Layout {
+    /// Sends this many bytes and a CRC, the RoboClaw acknowledges with 0xFF.
+    Write(usize),
+    /// Sends `request` bytes without a CRC, the RoboClaw answers with `response` bytes and a CRC.
+    Read { request: usize, response: usize },
+    /// The RoboClaw answers with a zero terminated string of up to 48 bytes and a CRC.
+    ReadString,
+}
+
+impl Commands {
+    pub const ALL: [Commands; 124] = [
+        Commands::M1Forward,
+        Commands::M1Backward,
+        Commands::SetMinMainVoltage,
+        Commands::SetMaxMainVoltage,
+        Commands::M2Forward,
+        Commands::M2Backward,
+        Commands::M1Drive,
+        Commands::M2Drive,
+        Commands::MixDriveForward,
+        Commands::MixDriveBackward,
+        Commands::MixTurnRight,
+        Commands::MixTurnLeft,
+        Commands::MixDrive,
+        Commands::TurnLeftRight,
+        Commands::SetSerialTimeout,
+        Commands::ReadSerialTimeout,
+        Commands::M1ReadEncoder,
+        Commands::M2ReadEncoder,
+        Commands::M1ReadSpeedCPS,
+        Commands::M2ReadSpeedCPS,
+        Commands::ResetEncoders,
+        Commands::ReadFirmwareVersion,
+        Commands::M1SetEncoder,
+        Commands::M2SetEncoder,
+        Commands::ReadMainBatVoltage,
+        Commands::ReadLogicBatVoltage,
+        Commands::SetMinLogicVoltage,
+        Commands::SetMaxLogicVoltage,
+        Commands::M1SetVelocityPIDConst,
+        Commands::M2SetVelocityPIDConst,
+        Commands::M1ReadRawSpeed,
+        Commands::M2ReadRawSpeed,
+        Commands::M1DriveSignedDutyCycle,
+        Commands::M2DriveSignedDutyCycle,
+        Commands::MixDriveSignedDutyCycle,
+        Commands::M1DriveSignedSpeed,
+        Commands::M2DriveSignedSpeed,
+        Commands::MixDriveSignedSpeed,
+        Commands::M1DriveSignedSpeedAccel,
+        Commands::M2DriveSignedSpeedAccel,
+        Commands::MixDriveSignedSpeedAccel,
+        Commands::M1DriveSignedSpeedDistanceBuffered,
+        Commands::M2DriveSignedSpeedDistanceBuffered,
+        Commands::MixDriveSignedSpeedDistanceBuffered,
+        Commands::M1DriveSignedSpeedAccelDistanceBuffered,
+        Commands::M2DriveSignedSpeedAccelDistanceBuffered,
+        Commands::MixDriveSignedSpeedAccelDistanceBuffered,
+        Commands::ReadBufferLength,
+        Commands::ReadMotorPWMs,
+        Commands::ReadMotorCurrents,
+        Commands::MixDriveIndividualSignedSpeedAccel,
+        Commands::MixDriveIndividualSignedSpeedAccelDistance,
+        Commands::M1DriveSignedDutyAccel,
+        Commands::M2DriveSignedDutyAccel,
+        Commands::MixDriveSignedDutyAccel,
+        Commands::M1ReadVelocityPIDConsts,
+        Commands::M2ReadVelocityPIDConsts,
+        Commands::SetMainBatVoltage,
+        Commands::SetLogicBatVoltage,
+        Commands::ReadMainBatVoltageSettings,
+        Commands::ReadLogicBatVoltageSettings,
+        Commands::M1SetPositionPIDConsts,
+        Commands::M2SetPositionPIDConsts,
+        Commands::M1ReadPositionPIDConsts,
+        Commands::M2ReadPositionPIDConsts,
+        Commands::M1DriveSpeedAccelDeccelPosition,
+        Commands::M2DriveSpeedAccelDeccelPosition,
+        Commands::MixDriveSpeedAccelDeccelPosition,
+        Commands::M1SetDefaultDutyCycle,
+        Commands::M2SetDefaultDutyCycle,
+        Commands::M1SetDefaultSpeed,
+        Commands::M2SetDefaultSpeed,
+        Commands::ReadDefaultSpeedSettings,
+        Commands::SetS3S4S5Modes,
+        Commands::ReadS3S4S5Modes,
+        Commands::SetDeadBandRCAnalog,
+        Commands::ReadDeadBandRCAnalog,
+        Commands::ReadEncoderCounts,
+        Commands::ReadRawMotorSpeeds,
+        Commands::RestoreDefaults,
+        Commands::ReadDefaultDutyCycleAccel,
+        Commands::ReadTemperature1,
+        Commands::ReadTemperature2,
+        Commands::ReadStatus,
+        Commands::ReadEncoderModes,
+        Commands::M1SetEncoderMode,
+        Commands::M2SetEncoderMode,
+        Commands::EEPROMWriteSettings,
+        Commands::EEPROMReadSettings,
+        Commands::SetStandardConfigSettings,
+        Commands::ReadStandardConfigSettings,
+        Commands::SetCTRLModes,
+        Commands::ReadCTRLModes,
+        Commands::SetCTRL1,
+        Commands::SetCTRL2,
+        Commands::ReadCTRLs,
+        Commands::M1SetAutoHomeDutySpeedTimeout,
+        Commands::M2SetAutoHomeDutySpeedTimeout,
+        Commands::ReadAutoHomeSettings,
+        Commands::ReadMotorAvgSpeed,
+        Commands::SetSpeedErrorLimits,
+        Commands::ReadSpeedErrorLimits,
+        Commands::ReadSpeedErrors,
+        Commands::SetPositionErrorLimits,
+        Commands::ReadPositionErrorLimits,
+        Commands::ReadPositionErrors,
+        Commands::SetBatteryVoltageOffsets,
+        Commands::ReadBatteryVoltageOffsets,
+        Commands::SetCurrentBlankingPercentages,
+        Commands::ReadCurrentBlankinPercentages,
+        Commands::M1DrivePosition,
+        Commands::M2DrivePosition,
+        Commands::MixDrivePosition,
+        Commands::M1DriveSpeedPosition,
+        Commands::M2DriveSpeedPosition,
+        Commands::MixDriveSpeedPosition,
+        Commands::M1SetMaxCurrent,
+        Commands::M2SetMaxCurrent,
+        Commands::M1ReadMaxCurrent,
+        Commands::M2ReadMaxCurrent,
+        Commands::SetPWMMode,
+        Commands::ReadPWMMode,
+        Commands::EEPROMReadUserMemoryLocation,
+        Commands::EEPROMWriteUserMemoryLocation,
+    ];
+
+    pub fn from_byte(byte: u8) -> Option<Commands> {
+        Self::ALL.iter().find(|command| **command as u8 == byte).copied()
+    }
+
+    /// Layout of the packet, None for commands whose layout isn't known.
+    pub fn layout(&self) -> Option<Layout> {
+        let read = |response: usize| Layout::Read { request: 0, response };
+        Some(match *self as u8 {
+            0..=14 | 26 | 27 | 92 | 93 | 148 => Layout::Write(1),
+            20 | 80 | 94 => Layout::Write(0),
+            32 | 33 | 70 | 71 | 76 | 98 | 100 | 102 | 103 | 115 => Layout::Write(2),
+            74 | 253 => Layout::Write(3),
+            22 | 23 | 34..=36 | 57 | 58 | 68 | 69 | 117 => Layout::Write(4),
+            119 | 120 => Layout::Write(5),
+            52 | 53 | 105 | 106 => Layout::Write(6),
+            37..=39 | 109 | 112 | 133 | 134 => Layout::Write(8),
+            41 | 42 | 121..=123 => Layout::Write(9),
+            40 | 54 => Layout::Write(12),
+            44 | 45 => Layout::Write(13),
+            28 | 29 | 50 => Layout::Write(16),
+            43 | 65 | 66 | 124 => Layout::Write(17),
+            46 => Layout::Write(21),
+            51 => Layout::Write(25),
+            61 | 62 => Layout::Write(28),
+            67 => Layout::Write(33),
+            15 | 149 => read(1),
+            24 | 25 | 47 | 77 | 82 | 83 | 91 | 99 | 101 | 116 => read(2),
+            75 => read(3),
+            48 | 49 | 59 | 60 | 72 | 90 | 104 | 118 => read(4),
+            16..=19 | 30 | 31 => read(5),
+            78 | 79 | 81 | 108 | 110 | 111 | 113 | 114 | 135 | 136 => read(8),
+            107 => read(12),
+            55 | 56 => read(16),
+            63 | 64 => read(28),
+            252 => Layout::Read { request: 1, response: 2 },
+            21 => Layout::ReadString,
+            _ => return None,
+        })
+    }
+}
@@ -1,3 +1,5 @@
+use anyhow::{anyhow, Result};
+
 pub fn calculate_encoder(current_encoder_value: i64, motor_encoder: Vec<u32>) -> i64 {
     let bits: [u8; 8] = get_bits(motor_encoder[1] as u8);
 
@@ -14,6 +16,13 @@ pub fn calculate_encoder(current_encoder_value: i64, motor_encoder: Vec<u32>) ->
     sum
 }
 
+/// Moves the accumulated encoder value to a raw 32 bit count taking the shortest way, which handles
+/// overflow and underflow as long as the encoder moves less than 2^31 counts between two reads.
+pub fn unwrap_encoder(current_encoder_value: i64, raw_count: u32) -> i64 {
+    let delta: i64 = raw_count.wrapping_sub(current_encoder_value as u32) as i32 as i64;
+    current_encoder_value + delta
+}
+
 pub fn get_bits(byte: u8) -> [u8; 8] {
     let mut bits: [u8; 8] = [0; 8];
     for i in 0..8 {
@@ -21,3 +30,26 @@ pub fn get_bits(byte: u8) -> [u8; 8] {
     }
     bits
 }
+
+/// Converts a value to the controller's fixed point units, e.g. volts to 0.1V steps with `scale = 10.0`.
+pub fn to_fixed<T: TryFrom<i64>>(value: f32, scale: f32) -> Result<T> {
+    let fixed: f32 = (value * scale).round();
+    if !fixed.is_finite() {
+        return Err(anyhow!("invalid value: {}", value));
+    }
+    T::try_from(fixed as i64).map_err(|_| anyhow!("invalid value: {} is out of range", value))
+}
+
+/// Rounds a converted value to whole encoder counts.
+pub fn to_counts<T: TryFrom<i64>>(value: f64) -> Result<T> {
+    let counts: f64 = value.round();
+    if !counts.is_finite() {
+        return Err(anyhow!("invalid value: {}", value));
+    }
+    T::try_from(counts as i64).map_err(|_| anyhow!("invalid value: {} counts are out of range", counts))
+}
+
+/// Formats bytes as space separated upper case hex, e.g. `80 10 1F`.
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<String>>().join(" ")
+}
@@ -0,0 +1,381 @@
+use anyhow::{anyhow, Result};
+use pyo3::prelude::*;
+
+const ENCODER_ABSOLUTE: u8 = 0b0000_0001;
+const ENCODER_REVERSE_MOTOR: u8 = 0b0010_0000;
+const ENCODER_REVERSE_ENCODER: u8 = 0b0100_0000;
+const ENCODER_RC_ANALOG: u8 = 0b1000_0000;
+
+/// Encoder mode flags as used by commands 91 - 93.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[pyclass(eq, get_all, set_all)]
+pub struct EncoderMode {
+    pub absolute: bool,
+    pub reverse_motor: bool,
+    pub reverse_encoder: bool,
+    pub rc_analog: bool,
+}
+
+#[pymethods]
+impl EncoderMode {
+    #[new]
+    #[pyo3(signature = (absolute=false, reverse_motor=false, reverse_encoder=false, rc_analog=false))]
+    fn new(absolute: bool, reverse_motor: bool, reverse_encoder: bool, rc_analog: bool) -> Self {
+        Self {
+            absolute,
+            reverse_motor,
+            reverse_encoder,
+            rc_analog,
+        }
+    }
+
+    #[staticmethod]
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            absolute: bits & ENCODER_ABSOLUTE != 0,
+            reverse_motor: bits & ENCODER_REVERSE_MOTOR != 0,
+            reverse_encoder: bits & ENCODER_REVERSE_ENCODER != 0,
+            rc_analog: bits & ENCODER_RC_ANALOG != 0,
+        }
+    }
+
+    #[getter]
+    pub fn bits(&self) -> u8 {
+        let mut bits: u8 = 0;
+        if self.absolute {
+            bits |= ENCODER_ABSOLUTE;
+        }
+        if self.reverse_motor {
+            bits |= ENCODER_REVERSE_MOTOR;
+        }
+        if self.reverse_encoder {
+            bits |= ENCODER_REVERSE_ENCODER;
+        }
+        if self.rc_analog {
+            bits |= ENCODER_RC_ANALOG;
+        }
+        bits
+    }
+
+    #[getter]
+    fn quadrature(&self) -> bool {
+        !self.absolute
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "EncoderMode(absolute={}, reverse_motor={}, reverse_encoder={}, rc_analog={})",
+            py_bool(self.absolute),
+            py_bool(self.reverse_motor),
+            py_bool(self.reverse_encoder),
+            py_bool(self.rc_analog),
+        )
+    }
+}
+
+/// RoboClaw models, used to validate current limits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(eq, eq_int)]
+pub enum Model {
+    RoboClaw2x7A,
+    RoboClaw2x15A,
+    RoboClaw2x30A,
+    RoboClaw2x45A,
+    RoboClaw2x60A,
+    RoboClaw2x80A,
+    RoboClaw2x120A,
+    RoboClaw2x160A,
+    RoboClaw2x200A,
+}
+
+#[pymethods]
+impl Model {
+    /// Peak current per channel in amps.
+    #[getter]
+    pub fn peak_current(&self) -> f32 {
+        match self {
+            Model::RoboClaw2x7A => 15.0,
+            Model::RoboClaw2x15A => 30.0,
+            Model::RoboClaw2x30A => 60.0,
+            Model::RoboClaw2x45A => 60.0,
+            Model::RoboClaw2x60A => 120.0,
+            Model::RoboClaw2x80A => 120.0,
+            Model::RoboClaw2x120A => 160.0,
+            Model::RoboClaw2x160A => 240.0,
+            Model::RoboClaw2x200A => 240.0,
+        }
+    }
+}
+
+/// Modes of the S3, S4 and S5 pins as used by commands 74 and 75.
+/// Not every mode is available on every pin, the RoboClaw rejects invalid combinations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "cli", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[pyclass(eq, eq_int)]
+pub enum PinMode {
+    Disabled = 0x00,
+    EStopLatching = 0x01,
+    EStop = 0x02,
+    VoltageClamp = 0x03,
+    Home = 0x04,
+    LimitForward = 0x12,
+    LimitReverse = 0x22,
+    LimitBoth = 0x32,
+    HomeUser = 0x62,
+    HomeAuto = 0xE2,
+}
+
+impl PinMode {
+    pub fn from_byte(byte: u8) -> Result<Self> {
+        Ok(match byte {
+            0x00 => PinMode::Disabled,
+            0x01 => PinMode::EStopLatching,
+            0x02 => PinMode::EStop,
+            0x03 => PinMode::VoltageClamp,
+            0x04 => PinMode::Home,
+            0x12 => PinMode::LimitForward,
+            0x22 => PinMode::LimitReverse,
+            0x32 => PinMode::LimitBoth,
+            0x62 => PinMode::HomeUser,
+            0xE2 => PinMode::HomeAuto,
+            _ => return Err(anyhow!("unknown pin mode: {:#04x}", byte)),
+        })
+    }
+}
+
+/// Modes of the CTRL1 and CTRL2 outputs as used by commands 100 and 101.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(eq, eq_int)]
+pub enum CtrlMode {
+    Disabled = 0,
+    User = 1,
+    VoltageClamp = 2,
+    Brake = 3,
+}
+
+impl CtrlMode {
+    pub fn from_byte(byte: u8) -> Result<Self> {
+        Ok(match byte {
+            0 => CtrlMode::Disabled,
+            1 => CtrlMode::User,
+            2 => CtrlMode::VoltageClamp,
+            3 => CtrlMode::Brake,
+            _ => return Err(anyhow!("unknown ctrl mode: {}", byte)),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(eq, eq_int)]
+pub enum Ctrl {
+    CTRL1 = 1,
+    CTRL2 = 2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "cli", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[pyclass(eq, eq_int)]
+pub enum PwmMode {
+    LockedAntiphase = 0,
+    SignMagnitude = 1,
+}
+
+impl PwmMode {
+    pub fn from_byte(byte: u8) -> Result<Self> {
+        Ok(match byte {
+            0 => PwmMode::LockedAntiphase,
+            1 => PwmMode::SignMagnitude,
+            _ => return Err(anyhow!("unknown pwm mode: {}", byte)),
+        })
+    }
+}
+
+/// Default duty cycle accelerations (commands 68, 69 and 81) and default speeds (commands 70 - 72).
+/// Used when the RoboClaw runs from RC/Analog input or after a serial timeout.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[pyclass(eq, get_all, set_all)]
+pub struct Defaults {
+    pub m1_duty_accel: u32,
+    pub m2_duty_accel: u32,
+    pub m1_speed: u16,
+    pub m2_speed: u16,
+}
+
+#[pymethods]
+impl Defaults {
+    #[new]
+    #[pyo3(signature = (m1_duty_accel=0, m2_duty_accel=0, m1_speed=0, m2_speed=0))]
+    fn new(m1_duty_accel: u32, m2_duty_accel: u32, m1_speed: u16, m2_speed: u16) -> Self {
+        Self {
+            m1_duty_accel,
+            m2_duty_accel,
+            m1_speed,
+            m2_speed,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Defaults(m1_duty_accel={}, m2_duty_accel={}, m1_speed={}, m2_speed={})",
+            self.m1_duty_accel, self.m2_duty_accel, self.m1_speed, self.m2_speed
+        )
+    }
+}
+
+const CONFIG_MODE: u16 = 0x0003;
+const CONFIG_BATTERY: u16 = 0x001C;
+const CONFIG_BAUD: u16 = 0x00E0;
+const CONFIG_ADDRESS: u16 = 0x0700;
+const CONFIG_SWAP_BUTTONS: u16 = 0x4000;
+const CONFIG_MULTI_UNIT: u16 = 0x8000;
+
+const BAUD_RATES: [u32; 8] = [2400, 9600, 19200, 38400, 57600, 115200, 230400, 460800];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(eq, eq_int)]
+pub enum ControlMode {
+    RC = 0,
+    Analog = 1,
+    SimpleSerial = 2,
+    PacketSerial = 3,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(eq, eq_int)]
+pub enum BatteryMode {
+    Off = 0,
+    Auto = 1,
+    Cell2 = 2,
+    Cell3 = 3,
+    Cell4 = 4,
+    Cell5 = 5,
+    Cell6 = 6,
+    Cell7 = 7,
+}
+
+/// Standard config settings word as used by commands 98 and 99.
+/// Bits that aren't exposed are kept as they were read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(eq)]
+pub struct StandardConfig {
+    #[pyo3(get)]
+    pub bits: u16,
+}
+
+#[pymethods]
+impl StandardConfig {
+    #[new]
+    pub fn new(bits: u16) -> Self {
+        Self { bits }
+    }
+
+    #[getter]
+    pub fn mode(&self) -> ControlMode {
+        match self.bits & CONFIG_MODE {
+            0 => ControlMode::RC,
+            1 => ControlMode::Analog,
+            2 => ControlMode::SimpleSerial,
+            _ => ControlMode::PacketSerial,
+        }
+    }
+
+    #[setter]
+    pub fn set_mode(&mut self, mode: ControlMode) {
+        self.bits = (self.bits & !CONFIG_MODE) | mode as u16;
+    }
+
+    #[getter]
+    pub fn battery_mode(&self) -> BatteryMode {
+        match (self.bits & CONFIG_BATTERY) >> 2 {
+            0 => BatteryMode::Off,
+            1 => BatteryMode::Auto,
+            2 => BatteryMode::Cell2,
+            3 => BatteryMode::Cell3,
+            4 => BatteryMode::Cell4,
+            5 => BatteryMode::Cell5,
+            6 => BatteryMode::Cell6,
+            _ => BatteryMode::Cell7,
+        }
+    }
+
+    #[setter]
+    pub fn set_battery_mode(&mut self, battery_mode: BatteryMode) {
+        self.bits = (self.bits & !CONFIG_BATTERY) | (battery_mode as u16) << 2;
+    }
+
+    /// Only used in the serial modes, the same bits select mixing/exponential/MCU in RC and Analog mode.
+    #[getter]
+    pub fn baud_rate(&self) -> u32 {
+        BAUD_RATES[((self.bits & CONFIG_BAUD) >> 5) as usize]
+    }
+
+    #[setter]
+    pub fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        let index: usize = BAUD_RATES
+            .iter()
+            .position(|&rate| rate == baud_rate)
+            .ok_or_else(|| anyhow!("invalid value: {} needs to be one of {:?}", baud_rate, BAUD_RATES))?;
+        self.bits = (self.bits & !CONFIG_BAUD) | (index as u16) << 5;
+        Ok(())
+    }
+
+    #[getter]
+    pub fn packet_address(&self) -> u8 {
+        0x80 + ((self.bits & CONFIG_ADDRESS) >> 8) as u8
+    }
+
+    #[setter]
+    pub fn set_packet_address(&mut self, packet_address: u8) -> Result<()> {
+        if !(0x80..=0x87).contains(&packet_address) {
+            return Err(anyhow!("invalid value: {} needs to be between 128 and 135", packet_address));
+        }
+        self.bits = (self.bits & !CONFIG_ADDRESS) | ((packet_address - 0x80) as u16) << 8;
+        Ok(())
+    }
+
+    #[getter]
+    pub fn swap_buttons(&self) -> bool {
+        self.bits & CONFIG_SWAP_BUTTONS != 0
+    }
+
+    #[setter]
+    pub fn set_swap_buttons(&mut self, swap_buttons: bool) {
+        self.set_flag(CONFIG_SWAP_BUTTONS, swap_buttons);
+    }
+
+    #[getter]
+    pub fn multi_unit(&self) -> bool {
+        self.bits & CONFIG_MULTI_UNIT != 0
+    }
+
+    #[setter]
+    pub fn set_multi_unit(&mut self, multi_unit: bool) {
+        self.set_flag(CONFIG_MULTI_UNIT, multi_unit);
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "StandardConfig(mode={:?}, battery_mode={:?}, baud_rate={}, packet_address={}, swap_buttons={}, multi_unit={})",
+            self.mode(),
+            self.battery_mode(),
+            self.baud_rate(),
+            self.packet_address(),
+            py_bool(self.swap_buttons()),
+            py_bool(self.multi_unit()),
+        )
+    }
+}
+
+impl StandardConfig {
+    fn set_flag(&mut self, flag: u16, value: bool) {
+        if value {
+            self.bits |= flag;
+        } else {
+            self.bits &= !flag;
+        }
+    }
+}
+
+fn py_bool(value: bool) -> &'static str {
+    if value { "True" } else { "False" }
+}
@@ -1,35 +1,119 @@
-use serialport::{SerialPort, ClearBuffer};
-use std::{time::Duration, sync::{Arc, Mutex}};
+use serialport::SerialPort;
+use std::{path::Path, time::{Duration, Instant}, sync::{Arc, Mutex}};
 use anyhow::{anyhow, Context, Result};
-use super::{commands::Commands, Crc16};
+use log::{debug, log_enabled, Level};
+use pyo3::prelude::*;
+use super::{commands::Commands, common::hex, transport::{RecordingTransport, Transport}, Crc16};
+
+/// Target of the packet trace, forwarded to the Python logger `roboclaw_python.packets`.
+pub const PACKET_TARGET: &str = "roboclaw_python::packets";
 
 pub struct Connection {
-    port: Arc<Mutex<Box<dyn SerialPort>>>,
+    port: Arc<Mutex<Box<dyn Transport>>>,
     retries: u8,
-    crc: Crc16
+    crc: Crc16,
+    frame: Option<Frame>,
+    stats: CommStats,
+}
+
+/// Communication counters of a connection, every retry counts as its own packet.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[pyclass(eq, get_all)]
+pub struct CommStats {
+    pub packets: u64,
+    pub timeouts: u64,
+    pub crc_errors: u64,
+    pub nacks: u64,
+    pub io_errors: u64,
+}
+
+#[pymethods]
+impl CommStats {
+    /// Number of packets that failed for any reason.
+    #[getter]
+    pub fn errors(&self) -> u64 {
+        self.timeouts + self.crc_errors + self.nacks + self.io_errors
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CommStats(packets={}, timeouts={}, crc_errors={}, nacks={}, io_errors={})",
+            self.packets, self.timeouts, self.crc_errors, self.nacks, self.io_errors
+        )
+    }
+}
+
+/// Bytes exchanged in the current transaction, only collected while packet tracing is enabled.
+struct Frame {
+    address: u8,
+    command: Commands,
+    started: Instant,
+    sent: Vec<u8>,
+    sent_crc: Option<u16>,
+    received: Vec<u8>,
+    received_crc: Option<u16>,
 }
 
 impl Connection {
-    pub fn new(port_name: &str, baud_rate: u32, timeout: Duration, retries: u8) -> Result<Self> {
+    /// Opens a serial port, everything sent and received is also written to `capture` if given.
+    pub fn new(port_name: &str, baud_rate: u32, timeout: Duration, retries: u8, capture: Option<&Path>) -> Result<Self> {
         let port: Box<dyn SerialPort> = serialport::new(port_name, baud_rate)
             .timeout(timeout)
             .open()
             .with_context(|| format!("error while while creating a new serialport using following values:\nport_name: {}\nbaud_rate: {}\ntimout: {:?}\nretries: {}", port_name, baud_rate, timeout, retries))?;
 
-        Ok(Self {
-            port: Arc::new(Mutex::new(port)),
+        let transport: Box<dyn Transport> = match capture {
+            Some(capture) => Box::new(RecordingTransport::create(Box::new(port), capture)?),
+            None => Box::new(port),
+        };
+        Ok(Self::with_transport(transport, retries))
+    }
+
+    /// Talks over any transport, e.g. a `RecordingTransport` or a `ReplayTransport` acting as fake device.
+    pub fn with_transport(transport: Box<dyn Transport>, retries: u8) -> Self {
+        Self {
+            port: Arc::new(Mutex::new(transport)),
             retries,
             crc: Crc16::new(),
-        })
+            frame: None,
+            stats: CommStats::default(),
+        }
+    }
+
+    pub fn stats(&self) -> CommStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = CommStats::default();
+    }
+
+    pub fn baud_rate(&self) -> Result<u32> {
+        self.port.lock().unwrap().baud_rate()
+    }
+
+    pub fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        self.port.lock().unwrap().set_baud_rate(baud_rate)?;
+        self.reset_connection()
     }
 
     fn reset_connection(&mut self) -> Result<()> {
-        self.port.lock().unwrap().clear(ClearBuffer::Input)?;
+        self.port.lock().unwrap().clear_input()?;
         self.crc.clear();
         Ok(())
     }
 
     fn send_command(&mut self, address: u8, command: Commands) -> Result<()> {
+        self.frame = log_enabled!(target: PACKET_TARGET, Level::Debug).then(|| Frame {
+            address,
+            command,
+            started: Instant::now(),
+            sent: Vec::new(),
+            sent_crc: None,
+            received: Vec::new(),
+            received_crc: None,
+        });
+        self.stats.packets += 1;
         self.crc.update(address);
         self.port.lock().unwrap().write_all(&[address])?;
         self.crc.update(command as u8);
@@ -62,35 +146,91 @@ impl Connection {
         Err(anyhow!("timeout after {}", self.retries))
     }
 
+    pub fn write_sized(&mut self, address: u8, command: Commands, values: &[u32], how: Vec<u8>) -> Result<()> {
+        for _ in 0..self.retries {
+            self.reset_connection()?;
+            self.send_command(address, command)?;
+            self.write_values(values, &how)?;
+
+            if self.verify_write_checksum()? {
+                return Ok(());
+            }
+        }
+
+        Err(anyhow!("timeout after {}", self.retries))
+    }
+
+    fn write_values(&mut self, values: &[u32], how: &[u8]) -> Result<()> {
+        if values.len() != how.len() {
+            return Err(anyhow!("got {} values but {} byte sizes", values.len(), how.len()));
+        }
+
+        for (&val, byte_size) in values.iter().zip(how) {
+            match byte_size {
+                1 => self.write_u8(val as u8)?,
+                2 => self.write_u16(val as u16)?,
+                4 => self.write_u32(val)?,
+                _ => return Err(anyhow!("invalid value: {} needs to be 1, 2 or 4", byte_size)),
+            }
+        }
+        Ok(())
+    }
+
     fn write_u8(&mut self, byte: u8) -> Result<()> {
         self.crc.update(byte);
-        self.port.lock().unwrap().write_all(&[byte])?;
-        Ok(())
+        self.write_raw(&[byte])
     }
 
     fn write_u16(&mut self, value: u16) -> Result<()> {
         let bytes: [u8; 2] = value.to_be_bytes();
         self.crc.update_bytes(&bytes);
-        self.port.lock().unwrap().write_all(&bytes)?;
-        Ok(())
+        self.write_raw(&bytes)
     }
 
     fn write_u32(&mut self, value: u32) -> Result<()> {
         let bytes: [u8; 4] = value.to_be_bytes();
         self.crc.update_bytes(&bytes);
-        self.port.lock().unwrap().write_all(&bytes)?;
+        self.write_raw(&bytes)
+    }
+
+    fn write_raw(&mut self, bytes: &[u8]) -> Result<()> {
+        if let Some(frame) = &mut self.frame {
+            frame.sent.extend_from_slice(bytes);
+        }
+        self.port.lock().unwrap().write_all(bytes)?;
         Ok(())
     }
 
     fn verify_write_checksum(&mut self) -> Result<bool> {
-        let crc_bytes: [u8; 2] = self.crc.get().to_be_bytes();
-        self.port.lock().unwrap().write_all(&crc_bytes)?;
+        let crc: u16 = self.crc.get();
+        if let Some(frame) = &mut self.frame {
+            frame.sent_crc = Some(crc);
+        }
+        self.port.lock().unwrap().write_all(&crc.to_be_bytes())?;
 
         let mut ack: [u8; 1] = [0u8; 1];
-        match self.port.lock().unwrap().read_exact(&mut ack) {
-            Ok(_) => Ok(ack[0] == 0xFF),
-            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => Ok(false),
-            Err(e) => Err(e.into()),
+        let result = self.port.lock().unwrap().read_exact(&mut ack);
+        match result {
+            Ok(_) => {
+                if let Some(frame) = &mut self.frame {
+                    frame.received.push(ack[0]);
+                }
+                if ack[0] != 0xFF {
+                    self.stats.nacks += 1;
+                }
+                self.trace_frame(if ack[0] == 0xFF { "ack" } else { "nack" });
+                Ok(ack[0] == 0xFF)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+                self.stats.timeouts += 1;
+                self.trace_frame("timeout");
+                Ok(false)
+            }
+            Err(e) => {
+                self.stats.io_errors += 1;
+                self.trace_frame(&format!("error: {}", e));
+                Err(e.into())
+            }
         }
     }
 
@@ -99,9 +239,14 @@ impl Connection {
     //----------------------------------------------------------------------------------------------------------------------------------------------//
 
     pub fn read(&mut self, address: u8, command: Commands, how: Vec<u8>) -> Result<Vec<u32>> {
+        self.read_with(address, command, &[], vec![], how)
+    }
+
+    pub fn read_with(&mut self, address: u8, command: Commands, values: &[u32], values_how: Vec<u8>, how: Vec<u8>) -> Result<Vec<u32>> {
         for _ in 0..self.retries {
             self.reset_connection()?;
             self.send_command(address, command)?;
+            self.write_values(values, &values_how)?;
 
             let mut data: Vec<_> = Vec::new();
             for byte_size in &how {
@@ -123,20 +268,101 @@ impl Connection {
         Err(anyhow!("timeout after {}", self.retries))
     }
 
+    /// Reads a zero terminated string of at most `max_length` bytes, e.g. the firmware version.
+    pub fn read_string(&mut self, address: u8, command: Commands, max_length: usize) -> Result<String> {
+        for _ in 0..self.retries {
+            self.reset_connection()?;
+            self.send_command(address, command)?;
+
+            let mut data: Vec<u8> = Vec::new();
+            while data.len() < max_length {
+                let byte: u8 = self.read_bytes(1)?[0];
+                if byte == 0 {
+                    break;
+                }
+                data.push(byte);
+            }
+
+            if self.read_checksum()? {
+                return Ok(String::from_utf8_lossy(&data).trim_end().to_string());
+            }
+        }
+
+        Err(anyhow!("timeout after {}", self.retries))
+    }
+
     fn read_checksum(&mut self) -> Result<bool> {
-        let crc: Vec<u8> = self.read_bytes(2)?;
-        if self.crc.get() == u16::from_be_bytes([crc[0], crc[1]]) {
+        let mut crc: [u8; 2] = [0u8; 2];
+        self.read_raw(&mut crc)?;
+        let crc: u16 = u16::from_be_bytes(crc);
+        if let Some(frame) = &mut self.frame {
+            frame.received_crc = Some(crc);
+        }
+
+        if self.crc.get() == crc {
+            self.trace_frame("ok");
             return Ok(true);
         }
+        self.stats.crc_errors += 1;
+        self.trace_frame(&format!("crc mismatch, expected 0x{:04X}", self.crc.get()));
         Err(anyhow!("crc mismatch during reading"))
     }
 
     fn read_bytes(&mut self, byte_size: u8) -> Result<Vec<u8>>{
         let mut buf: Vec<u8> = vec![0u8; byte_size as usize];
-        self.port.lock().unwrap().read_exact(&mut buf)?;
+        self.read_raw(&mut buf)?;
+        if let Some(frame) = &mut self.frame {
+            frame.received.extend_from_slice(&buf);
+        }
         for b in &buf {
             self.crc.update(*b);
         }
         Ok(buf)
-    }   
-}
\ No newline at end of file
+    }
+
+    fn read_raw(&mut self, buf: &mut [u8]) -> Result<()> {
+        let result = self.port.lock().unwrap().read_exact(buf);
+        if let Err(e) = result {
+            match e.kind() {
+                std::io::ErrorKind::TimedOut => self.stats.timeouts += 1,
+                _ => self.stats.io_errors += 1,
+            }
+            self.trace_frame(&format!("error: {}", e));
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    //-----------------------------------------------------------------------------------------------------------------------------------------------//
+    //----------------------------------------------------------------[Packet Tracing]---------------------------------------------------------------//
+    //-----------------------------------------------------------------------------------------------------------------------------------------------//
+
+    /// Logs the sent and received bytes of the current transaction, if tracing was enabled when it started.
+    fn trace_frame(&mut self, status: &str) {
+        let Some(frame) = self.frame.take() else {
+            return;
+        };
+
+        let crc = |crc: Option<u16>| crc.map_or(String::new(), |crc| format!(" crc=0x{:04X}", crc));
+        debug!(
+            target: PACKET_TARGET,
+            "TX address=0x{:02X} command={:?}({}) payload=[{}]{}",
+            frame.address,
+            frame.command,
+            frame.command as u8,
+            hex(&frame.sent),
+            crc(frame.sent_crc)
+        );
+        debug!(
+            target: PACKET_TARGET,
+            "RX address=0x{:02X} command={:?}({}) payload=[{}]{} status={} latency={:.3}ms",
+            frame.address,
+            frame.command,
+            frame.command as u8,
+            hex(&frame.received),
+            crc(frame.received_crc),
+            status,
+            frame.started.elapsed().as_secs_f64() * 1000.0
+        );
+    }
+}
@@ -2,6 +2,12 @@ pub struct Crc16 {
     crc: u16,
 }
 
+impl Default for Crc16 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Crc16 {
     pub fn new() -> Self {
         Crc16 { crc: 0 }
@@ -0,0 +1,184 @@
+use super::roboclaw::{Motor, RoboClaw};
+use anyhow::{anyhow, Result};
+use pyo3::prelude::*;
+use std::f64::consts::PI;
+
+/// Geometry of a differential (skid-steer) base.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Kinematics {
+    /// Wheel radius in meters.
+    pub wheel_radius: f64,
+    /// Distance between the left and right wheels in meters.
+    pub track_width: f64,
+    /// Encoder counts per motor revolution.
+    pub counts_per_revolution: f64,
+    /// Motor revolutions per wheel revolution.
+    pub gear_ratio: f64,
+}
+
+impl Kinematics {
+    pub fn new(wheel_radius: f64, track_width: f64, counts_per_revolution: f64, gear_ratio: f64) -> Result<Self> {
+        for (name, value) in [
+            ("wheel_radius", wheel_radius),
+            ("track_width", track_width),
+            ("counts_per_revolution", counts_per_revolution),
+            ("gear_ratio", gear_ratio),
+        ] {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(anyhow!("invalid value: {} needs to be positive but is {}", name, value));
+            }
+        }
+
+        Ok(Self {
+            wheel_radius,
+            track_width,
+            counts_per_revolution,
+            gear_ratio,
+        })
+    }
+
+    /// Encoder counts per meter of wheel travel.
+    pub fn counts_per_meter(&self) -> f64 {
+        self.counts_per_revolution * self.gear_ratio / (2.0 * PI * self.wheel_radius)
+    }
+
+    pub fn meters_to_counts(&self, meters: f64) -> f64 {
+        meters * self.counts_per_meter()
+    }
+
+    pub fn counts_to_meters(&self, counts: f64) -> f64 {
+        counts / self.counts_per_meter()
+    }
+
+    /// Left and right wheel velocities in m/s for a linear (m/s) and angular (rad/s) velocity.
+    pub fn wheel_velocities(&self, linear: f64, angular: f64) -> (f64, f64) {
+        let offset: f64 = angular * self.track_width / 2.0;
+        (linear - offset, linear + offset)
+    }
+
+    /// Linear (m/s) and angular (rad/s) velocity for left and right wheel velocities in m/s.
+    pub fn body_velocity(&self, left: f64, right: f64) -> (f64, f64) {
+        ((left + right) / 2.0, (right - left) / self.track_width)
+    }
+}
+
+/// Drives a differential base with one RoboClaw using linear and angular velocities.
+#[pyclass]
+pub struct DifferentialDrive {
+    roboclaw: Py<RoboClaw>,
+    kinematics: Kinematics,
+    #[pyo3(get)]
+    left: Motor,
+    #[pyo3(get, set)]
+    address: Option<u8>,
+}
+
+#[pymethods]
+impl DifferentialDrive {
+    #[new]
+    #[pyo3(signature = (roboclaw, wheel_radius, track_width, counts_per_revolution, gear_ratio=1.0, left=Motor::M1, address=None))]
+    pub fn new(
+        roboclaw: Py<RoboClaw>,
+        wheel_radius: f64,
+        track_width: f64,
+        counts_per_revolution: f64,
+        gear_ratio: f64,
+        left: Motor,
+        address: Option<u8>,
+    ) -> Result<Self> {
+        Ok(Self {
+            roboclaw,
+            kinematics: Kinematics::new(wheel_radius, track_width, counts_per_revolution, gear_ratio)?,
+            left,
+            address,
+        })
+    }
+
+    #[getter]
+    fn wheel_radius(&self) -> f64 {
+        self.kinematics.wheel_radius
+    }
+
+    #[getter]
+    fn track_width(&self) -> f64 {
+        self.kinematics.track_width
+    }
+
+    #[getter]
+    fn counts_per_revolution(&self) -> f64 {
+        self.kinematics.counts_per_revolution
+    }
+
+    #[getter]
+    fn gear_ratio(&self) -> f64 {
+        self.kinematics.gear_ratio
+    }
+
+    /// Drives with a linear velocity in m/s and an angular velocity in rad/s.
+    pub fn drive(&self, py: Python<'_>, linear: f64, angular: f64) -> Result<bool> {
+        let (left, right) = self.kinematics.wheel_velocities(linear, angular);
+        let left: i32 = self.to_speed(left)?;
+        let right: i32 = self.to_speed(right)?;
+
+        let (m1_speed, m2_speed) = self.to_motors(left, right);
+        self.roboclaw
+            .borrow_mut(py)
+            .drive_speed(m1_speed, m2_speed, self.address)
+    }
+
+    pub fn stop(&self, py: Python<'_>) -> Result<bool> {
+        self.roboclaw.borrow_mut(py).drive_speed(0, 0, self.address)
+    }
+
+    /// Reads the left and right wheel velocities in m/s.
+    pub fn read_wheel_velocities(&self, py: Python<'_>) -> Result<(f64, f64)> {
+        let speeds: (i64, i64) = self.roboclaw.borrow_mut(py).read_speeds(self.address)?;
+        let (left, right) = self.to_motors(speeds.0, speeds.1);
+        Ok((
+            self.kinematics.counts_to_meters(left as f64),
+            self.kinematics.counts_to_meters(right as f64),
+        ))
+    }
+
+    /// Reads the linear (m/s) and angular (rad/s) velocity of the base.
+    pub fn read_velocity(&self, py: Python<'_>) -> Result<(f64, f64)> {
+        let (left, right) = self.read_wheel_velocities(py)?;
+        Ok(self.kinematics.body_velocity(left, right))
+    }
+
+    /// Reads the distance each wheel travelled in meters since the encoders were last reset.
+    pub fn read_wheel_distances(&self, py: Python<'_>) -> Result<(f64, f64)> {
+        let counts: (i64, i64) = self.roboclaw.borrow_mut(py).read_encoders(self.address)?;
+        let (left, right) = self.to_motors(counts.0, counts.1);
+        Ok((
+            self.kinematics.counts_to_meters(left as f64),
+            self.kinematics.counts_to_meters(right as f64),
+        ))
+    }
+}
+
+impl DifferentialDrive {
+    pub fn kinematics(&self) -> &Kinematics {
+        &self.kinematics
+    }
+
+    pub fn left(&self) -> Motor {
+        self.left
+    }
+
+    /// Swaps a (left, right) pair to (M1, M2) or the other way around.
+    fn to_motors<T>(&self, left: T, right: T) -> (T, T) {
+        match self.left {
+            Motor::M1 => (left, right),
+            Motor::M2 => (right, left),
+        }
+    }
+
+    fn to_speed(&self, velocity: f64) -> Result<i32> {
+        let counts: f64 = self.kinematics.meters_to_counts(velocity).round();
+        if !(i32::MIN as f64..=i32::MAX as f64).contains(&counts) {
+            return Err(anyhow!("invalid value: {} m/s is out of range", velocity));
+        }
+        Ok(counts as i32)
+    }
+}
@@ -0,0 +1,236 @@
+use super::kinematics::{DifferentialDrive, Kinematics};
+use super::roboclaw::{Motor, RoboClaw};
+use anyhow::{anyhow, Result};
+use pyo3::prelude::*;
+use std::f64::consts::PI;
+use std::time::Instant;
+
+/// Pose and velocity of a differential base at a point in time.
+#[derive(Debug, Clone, PartialEq)]
+#[pyclass(get_all)]
+pub struct OdometryEstimate {
+    pub x: f64,
+    pub y: f64,
+    pub heading: f64,
+    pub linear: f64,
+    pub angular: f64,
+    pub timestamp: f64,
+    /// Diagonal of the (x, y, heading) covariance.
+    pub pose_covariance: [f64; 3],
+    /// Diagonal of the (linear, angular) covariance.
+    pub twist_covariance: [f64; 2],
+}
+
+/// Integrates the pose of a differential base from accumulated encoder counts.
+#[pyclass]
+pub struct Odometry {
+    kinematics: Kinematics,
+    left: Motor,
+    estimate: OdometryEstimate,
+    last_counts: Option<(i64, i64)>,
+    pending: (Option<i64>, Option<i64>),
+    start: Instant,
+}
+
+#[pymethods]
+impl Odometry {
+    #[new]
+    #[pyo3(signature = (wheel_radius, track_width, counts_per_revolution, gear_ratio=1.0, left=Motor::M1, pose_covariance=[0.0; 3], twist_covariance=[0.0; 2]))]
+    pub fn new(
+        wheel_radius: f64,
+        track_width: f64,
+        counts_per_revolution: f64,
+        gear_ratio: f64,
+        left: Motor,
+        pose_covariance: [f64; 3],
+        twist_covariance: [f64; 2],
+    ) -> Result<Self> {
+        let kinematics: Kinematics = Kinematics::new(wheel_radius, track_width, counts_per_revolution, gear_ratio)?;
+        Self::with_kinematics(kinematics, left, pose_covariance, twist_covariance)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (drive, pose_covariance=[0.0; 3], twist_covariance=[0.0; 2]))]
+    pub fn from_drive(
+        drive: &DifferentialDrive,
+        pose_covariance: [f64; 3],
+        twist_covariance: [f64; 2],
+    ) -> Result<Self> {
+        Self::with_kinematics(*drive.kinematics(), drive.left(), pose_covariance, twist_covariance)
+    }
+
+    #[getter]
+    pub fn x(&self) -> f64 {
+        self.estimate.x
+    }
+
+    #[getter]
+    pub fn y(&self) -> f64 {
+        self.estimate.y
+    }
+
+    #[getter]
+    pub fn heading(&self) -> f64 {
+        self.estimate.heading
+    }
+
+    #[getter]
+    pub fn linear(&self) -> f64 {
+        self.estimate.linear
+    }
+
+    #[getter]
+    pub fn angular(&self) -> f64 {
+        self.estimate.angular
+    }
+
+    #[getter]
+    pub fn timestamp(&self) -> f64 {
+        self.estimate.timestamp
+    }
+
+    #[getter]
+    pub fn get_pose_covariance(&self) -> [f64; 3] {
+        self.estimate.pose_covariance
+    }
+
+    #[setter]
+    pub fn set_pose_covariance(&mut self, pose_covariance: [f64; 3]) -> Result<()> {
+        check_covariance(&pose_covariance)?;
+        self.estimate.pose_covariance = pose_covariance;
+        Ok(())
+    }
+
+    #[getter]
+    pub fn get_twist_covariance(&self) -> [f64; 2] {
+        self.estimate.twist_covariance
+    }
+
+    #[setter]
+    pub fn set_twist_covariance(&mut self, twist_covariance: [f64; 2]) -> Result<()> {
+        check_covariance(&twist_covariance)?;
+        self.estimate.twist_covariance = twist_covariance;
+        Ok(())
+    }
+
+    pub fn estimate(&self) -> OdometryEstimate {
+        self.estimate.clone()
+    }
+
+    /// Sets the pose and forgets the last encoder counts, the next update only sets the reference.
+    #[pyo3(signature = (x=0.0, y=0.0, heading=0.0))]
+    pub fn reset(&mut self, x: f64, y: f64, heading: f64) {
+        self.estimate.x = x;
+        self.estimate.y = y;
+        self.estimate.heading = normalize_angle(heading);
+        self.estimate.linear = 0.0;
+        self.estimate.angular = 0.0;
+        self.last_counts = None;
+        self.pending = (None, None);
+    }
+
+    /// Updates the pose from the accumulated counts of both motors, e.g. from `read_encoders`.
+    /// The timestamp is in seconds and defaults to the time since the odometry was created.
+    #[pyo3(signature = (m1_counts, m2_counts, timestamp=None))]
+    pub fn update(&mut self, m1_counts: i64, m2_counts: i64, timestamp: Option<f64>) -> OdometryEstimate {
+        let timestamp: f64 = timestamp.unwrap_or_else(|| self.start.elapsed().as_secs_f64());
+        let counts: (i64, i64) = match self.left {
+            Motor::M1 => (m1_counts, m2_counts),
+            Motor::M2 => (m2_counts, m1_counts),
+        };
+        self.pending = (None, None);
+
+        let Some(last_counts) = self.last_counts.replace(counts) else {
+            self.estimate.timestamp = timestamp;
+            return self.estimate();
+        };
+
+        let left: f64 = self.kinematics.counts_to_meters((counts.0 - last_counts.0) as f64);
+        let right: f64 = self.kinematics.counts_to_meters((counts.1 - last_counts.1) as f64);
+        let (distance, rotation) = self.kinematics.body_velocity(left, right);
+
+        let direction: f64 = self.estimate.heading + rotation / 2.0;
+        self.estimate.x += distance * direction.cos();
+        self.estimate.y += distance * direction.sin();
+        self.estimate.heading = normalize_angle(self.estimate.heading + rotation);
+
+        let elapsed: f64 = timestamp - self.estimate.timestamp;
+        if elapsed > 0.0 {
+            self.estimate.linear = distance / elapsed;
+            self.estimate.angular = rotation / elapsed;
+        }
+        self.estimate.timestamp = timestamp;
+        self.estimate()
+    }
+
+    /// Stores the accumulated counts of one motor, e.g. from `read_encoder` or one half of `read_encoders`.
+    /// The pose is updated once both motors got new counts.
+    #[pyo3(signature = (motor, counts, timestamp=None))]
+    pub fn update_motor(&mut self, motor: Motor, counts: i64, timestamp: Option<f64>) -> Option<OdometryEstimate> {
+        match motor {
+            Motor::M1 => self.pending.0 = Some(counts),
+            Motor::M2 => self.pending.1 = Some(counts),
+        }
+        match self.pending {
+            (Some(m1_counts), Some(m2_counts)) => Some(self.update(m1_counts, m2_counts, timestamp)),
+            _ => None,
+        }
+    }
+
+    /// Reads both encoders in a single transaction and updates the pose.
+    #[pyo3(signature = (roboclaw, address=None))]
+    pub fn update_from(&mut self, roboclaw: &mut RoboClaw, address: Option<u8>) -> Result<OdometryEstimate> {
+        let (m1_counts, m2_counts) = roboclaw.read_encoders(address)?;
+        Ok(self.update(m1_counts, m2_counts, None))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Odometry(x={}, y={}, heading={}, linear={}, angular={})",
+            self.estimate.x, self.estimate.y, self.estimate.heading, self.estimate.linear, self.estimate.angular
+        )
+    }
+}
+
+impl Odometry {
+    pub fn with_kinematics(
+        kinematics: Kinematics,
+        left: Motor,
+        pose_covariance: [f64; 3],
+        twist_covariance: [f64; 2],
+    ) -> Result<Self> {
+        check_covariance(&pose_covariance)?;
+        check_covariance(&twist_covariance)?;
+
+        Ok(Self {
+            kinematics,
+            left,
+            estimate: OdometryEstimate {
+                x: 0.0,
+                y: 0.0,
+                heading: 0.0,
+                linear: 0.0,
+                angular: 0.0,
+                timestamp: 0.0,
+                pose_covariance,
+                twist_covariance,
+            },
+            last_counts: None,
+            pending: (None, None),
+            start: Instant::now(),
+        })
+    }
+}
+
+fn check_covariance(values: &[f64]) -> Result<()> {
+    if values.iter().any(|value| !value.is_finite() || *value < 0.0) {
+        return Err(anyhow!("invalid value: covariances need to be positive, got {:?}", values));
+    }
+    Ok(())
+}
+
+/// Wraps an angle to (-pi, pi].
+fn normalize_angle(angle: f64) -> f64 {
+    let wrapped: f64 = (angle + PI).rem_euclid(2.0 * PI) - PI;
+    if wrapped == -PI { PI } else { wrapped }
+}
@@ -0,0 +1,338 @@
+use super::telemetry::{columns, without_gil, Channel, RingBuffer, Sample, TelemetryPoller};
+use anyhow::{anyhow, Context, Result};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+const MAGIC: &[u8; 4] = b"RCTL";
+const VERSION: u8 = 1;
+/// Name and `array.array` typecode of every column, in the order they are stored.
+const COLUMNS: [(&str, u8); 4] = [("timestamp", b'd'), ("channel", b'B'), ("value1", b'd'), ("value2", b'd')];
+/// Longest the recorder sleeps at once, so `stop` doesn't have to wait for the whole interval.
+const MAX_SLEEP: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(eq, eq_int)]
+pub enum RecordFormat {
+    /// One row per sample with the columns timestamp, channel, value1 and value2.
+    Csv,
+    /// Little endian binary file with a schema header followed by blocks of columns.
+    Columnar,
+}
+
+/// Writes samples to a CSV or columnar file.
+///
+/// The columnar file starts with the magic `RCTL`, the version, the number of columns and every column
+/// as name length, name and `array.array` typecode, then the number of channels and every channel name,
+/// so channel codes can be decoded without this crate. It is followed by blocks of a `u32` row count
+/// and the values of each column in turn.
+pub struct RecordWriter {
+    file: BufWriter<File>,
+    format: RecordFormat,
+}
+
+impl RecordWriter {
+    pub fn create(path: &Path, format: RecordFormat) -> Result<Self> {
+        let file: File = File::create(path).with_context(|| format!("couldn't create {}", path.display()))?;
+        let mut writer: Self = Self {
+            file: BufWriter::new(file),
+            format,
+        };
+
+        match format {
+            RecordFormat::Csv => {
+                let names: Vec<&str> = COLUMNS.iter().map(|(name, _)| *name).collect();
+                writeln!(writer.file, "{}", names.join(","))?;
+            }
+            RecordFormat::Columnar => {
+                writer.file.write_all(MAGIC)?;
+                writer.file.write_all(&[VERSION, COLUMNS.len() as u8])?;
+                for (name, typecode) in COLUMNS {
+                    writer.write_name(name)?;
+                    writer.file.write_all(&[typecode])?;
+                }
+                writer.file.write_all(&[Channel::ALL.len() as u8])?;
+                for channel in Channel::ALL {
+                    writer.write_name(&format!("{:?}", channel))?;
+                }
+            }
+        }
+        Ok(writer)
+    }
+
+    /// Appends the samples, in the columnar format as one block.
+    pub fn write(&mut self, samples: &[Sample]) -> Result<()> {
+        match self.format {
+            RecordFormat::Csv => {
+                for sample in samples {
+                    let [value1, value2] = sample.values.map(|value| {
+                        if value.is_nan() { String::new() } else { value.to_string() }
+                    });
+                    writeln!(self.file, "{:.6},{:?},{},{}", sample.timestamp, sample.channel, value1, value2)?;
+                }
+            }
+            RecordFormat::Columnar if !samples.is_empty() => {
+                self.file.write_all(&(samples.len() as u32).to_le_bytes())?;
+                for sample in samples {
+                    self.file.write_all(&sample.timestamp.to_le_bytes())?;
+                }
+                for sample in samples {
+                    self.file.write_all(&[sample.channel as u8])?;
+                }
+                for index in 0..2 {
+                    for sample in samples {
+                        self.file.write_all(&sample.values[index].to_le_bytes())?;
+                    }
+                }
+            }
+            RecordFormat::Columnar => {}
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.file.flush()?)
+    }
+
+    fn write_name(&mut self, name: &str) -> Result<()> {
+        self.file.write_all(&[name.len() as u8])?;
+        self.file.write_all(name.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Reads all samples of a file written in the columnar format.
+pub fn read_columnar(path: &Path) -> Result<Vec<Sample>> {
+    let file: File = File::open(path).with_context(|| format!("couldn't open {}", path.display()))?;
+    let mut reader: BufReader<File> = BufReader::new(file);
+
+    let mut header: [u8; 6] = [0u8; 6];
+    reader.read_exact(&mut header)?;
+    if &header[..4] != MAGIC {
+        return Err(anyhow!("{} isn't a columnar recording", path.display()));
+    }
+    if header[4] != VERSION {
+        return Err(anyhow!("unsupported recording version {}", header[4]));
+    }
+    let mut columns: Vec<(String, u8)> = Vec::new();
+    for _ in 0..header[5] {
+        columns.push((read_name(&mut reader)?, read_array::<1>(&mut reader)?[0]));
+    }
+    if columns.iter().map(|(name, typecode)| (name.as_str(), *typecode)).ne(COLUMNS) {
+        return Err(anyhow!("unexpected columns {:?}", columns));
+    }
+    for _ in 0..read_array::<1>(&mut reader)?[0] {
+        read_name(&mut reader)?;
+    }
+
+    let mut samples: Vec<Sample> = Vec::new();
+    loop {
+        let rows: [u8; 4] = match read_array::<4>(&mut reader) {
+            Result::Ok(rows) => rows,
+            Err(e) if e.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == ErrorKind::UnexpectedEof) => {
+                return Ok(samples);
+            }
+            Err(e) => return Err(e),
+        };
+        let rows: usize = u32::from_le_bytes(rows) as usize;
+
+        let start: usize = samples.len();
+        for _ in 0..rows {
+            samples.push(Sample {
+                timestamp: f64::from_le_bytes(read_array(&mut reader)?),
+                channel: Channel::Encoders,
+                values: [f64::NAN; 2],
+            });
+        }
+        for sample in &mut samples[start..] {
+            let code: u8 = read_array::<1>(&mut reader)?[0];
+            sample.channel = Channel::from_index(code).ok_or_else(|| anyhow!("unknown channel {}", code))?;
+        }
+        for index in 0..2 {
+            for sample in &mut samples[start..] {
+                sample.values[index] = f64::from_le_bytes(read_array(&mut reader)?);
+            }
+        }
+    }
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> Result<[u8; N]> {
+    let mut bytes: [u8; N] = [0u8; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_name(reader: &mut impl Read) -> Result<String> {
+    let mut name: Vec<u8> = vec![0u8; read_array::<1>(reader)?[0] as usize];
+    reader.read_exact(&mut name)?;
+    Ok(String::from_utf8(name)?)
+}
+
+/// State shared between the `TelemetryRecorder` and its thread.
+struct Shared {
+    running: AtomicBool,
+    rows: AtomicU64,
+    missed: AtomicU64,
+}
+
+/// Writes the samples of a `TelemetryPoller` to a file on a background thread.
+#[pyclass]
+pub struct TelemetryRecorder {
+    buffer: Arc<RingBuffer>,
+    shared: Arc<Shared>,
+    path: PathBuf,
+    #[pyo3(get)]
+    format: RecordFormat,
+    interval: Duration,
+    worker: Option<JoinHandle<Result<()>>>,
+}
+
+#[pymethods]
+impl TelemetryRecorder {
+    /// Samples are written every `interval` seconds, in the columnar format every write is one block.
+    #[new]
+    #[pyo3(signature = (poller, path, format=RecordFormat::Csv, interval=1.0))]
+    pub fn new(poller: &TelemetryPoller, path: PathBuf, format: RecordFormat, interval: f64) -> Result<Self> {
+        if !interval.is_finite() || interval <= 0.0 {
+            return Err(anyhow!("invalid value: {} needs to be a positive interval in seconds", interval));
+        }
+
+        Ok(Self {
+            buffer: Arc::clone(poller.buffer()),
+            shared: Arc::new(Shared {
+                running: AtomicBool::new(false),
+                rows: AtomicU64::new(0),
+                missed: AtomicU64::new(0),
+            }),
+            path,
+            format,
+            interval: Duration::try_from_secs_f64(interval)?,
+            worker: None,
+        })
+    }
+
+    /// Creates (or overwrites) the file and records every sample taken from now on.
+    pub fn start(&mut self) -> Result<()> {
+        if self.worker.is_some() {
+            return Err(anyhow!("already recording to {}", self.path.display()));
+        }
+
+        let writer: RecordWriter = RecordWriter::create(&self.path, self.format)?;
+        self.shared.running.store(true, Ordering::Release);
+        self.shared.rows.store(0, Ordering::Relaxed);
+        self.shared.missed.store(0, Ordering::Relaxed);
+
+        let buffer: Arc<RingBuffer> = Arc::clone(&self.buffer);
+        let shared: Arc<Shared> = Arc::clone(&self.shared);
+        let cursor: u64 = buffer.written();
+        let interval: Duration = self.interval;
+        let worker = thread::Builder::new()
+            .name("roboclaw-recorder".to_string())
+            .spawn(move || record(writer, buffer, cursor, interval, shared));
+        match worker {
+            Result::Ok(worker) => {
+                self.worker = Some(worker);
+                Ok(())
+            }
+            Err(e) => {
+                self.shared.running.store(false, Ordering::Release);
+                Err(anyhow!("couldn't start the recorder thread: {}", e))
+            }
+        }
+    }
+
+    /// Writes the remaining samples and closes the file. Raises the error if writing failed while recording.
+    pub fn stop(&mut self, py: Python<'_>) -> Result<()> {
+        py.allow_threads(|| self.join())
+    }
+
+    /// False once stopped or if writing failed.
+    #[getter]
+    pub fn recording(&self) -> bool {
+        self.worker.as_ref().is_some_and(|worker| !worker.is_finished())
+    }
+
+    #[getter]
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    #[getter]
+    pub fn interval(&self) -> f64 {
+        self.interval.as_secs_f64()
+    }
+
+    /// Number of samples written since the recording started.
+    #[getter]
+    pub fn rows(&self) -> u64 {
+        self.shared.rows.load(Ordering::Relaxed)
+    }
+
+    /// Number of samples overwritten in the ring buffer before they could be written.
+    #[getter]
+    pub fn missed(&self) -> u64 {
+        self.shared.missed.load(Ordering::Relaxed)
+    }
+
+    /// Loads a columnar recording in the same format as `TelemetryPoller.drain`.
+    #[staticmethod]
+    pub fn load<'py>(py: Python<'py>, path: PathBuf) -> Result<Bound<'py, PyDict>> {
+        let samples: Vec<Sample> = read_columnar(&path)?;
+        Ok(columns(py, &samples, None)?)
+    }
+}
+
+impl TelemetryRecorder {
+    fn join(&mut self) -> Result<()> {
+        let Some(worker) = self.worker.take() else {
+            return Ok(());
+        };
+
+        self.shared.running.store(false, Ordering::Release);
+        worker
+            .join()
+            .map_err(|_| anyhow!("the recorder thread panicked"))?
+    }
+}
+
+impl Drop for TelemetryRecorder {
+    fn drop(&mut self) {
+        without_gil(|| {
+            let _ = self.join();
+        });
+    }
+}
+
+/// Writes the new samples every interval until `running` is cleared, then writes the rest.
+fn record(
+    mut writer: RecordWriter,
+    buffer: Arc<RingBuffer>,
+    mut cursor: u64,
+    interval: Duration,
+    shared: Arc<Shared>,
+) -> Result<()> {
+    loop {
+        let running: bool = shared.running.load(Ordering::Acquire);
+        let (samples, head) = buffer.read_from(cursor);
+        shared.missed.fetch_add(head - cursor - samples.len() as u64, Ordering::Relaxed);
+        cursor = head;
+
+        writer.write(&samples)?;
+        writer.flush()?;
+        shared.rows.fetch_add(samples.len() as u64, Ordering::Relaxed);
+        if !running {
+            return Ok(());
+        }
+
+        let started: Instant = Instant::now();
+        while shared.running.load(Ordering::Acquire) && started.elapsed() < interval {
+            thread::sleep(interval.saturating_sub(started.elapsed()).min(MAX_SLEEP));
+        }
+    }
+}
@@ -1,9 +1,32 @@
 use super::commands::Commands;
-use super::common::calculate_encoder;
-use super::connection::Connection;
-use anyhow::{Context, Ok, Result};
+use super::common::{to_counts, to_fixed, unwrap_encoder};
+use super::config::{
+    ControlMode, Ctrl, CtrlMode, Defaults, EncoderMode, Model, PinMode, PwmMode, StandardConfig,
+};
+use super::connection::{CommStats, Connection};
+use super::transport::ReplayTransport;
+use super::status::{Status, StatusFlag};
+use super::trajectory::Segment;
+use super::units::{MotorUnits, Unit};
+use super::user_memory::{UserMemory, UserValue, USER_MEMORY_WORDS};
+use anyhow::{anyhow, Context, Ok, Result};
 use pyo3::prelude::*;
-use std::time::Duration;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Highest current limit of any model in amps.
+const MAX_CURRENT: f32 = 240.0;
+/// Highest duty cycle acceleration, 655359 accelerates from 0 to 100% in 1/10 of a second.
+const MAX_DUTY_ACCEL: u32 = 655359;
+/// Highest RC/Analog deadband in percent.
+const MAX_DEADBAND: f32 = 25.0;
+/// Buffer length reported once the buffer is empty and the last command finished.
+const BUFFER_EMPTY: u8 = 0x80;
+const HOME_POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// Longest firmware version string including the line break.
+const MAX_VERSION_LENGTH: usize = 48;
 
 #[pyclass]
 pub struct RoboClaw {
@@ -11,6 +34,10 @@ pub struct RoboClaw {
     address: u8,
     encoder_value_m1: i64,
     encoder_value_m2: i64,
+    #[pyo3(get, set)]
+    model: Option<Model>,
+    units_m1: Option<MotorUnits>,
+    units_m2: Option<MotorUnits>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -20,36 +47,54 @@ pub enum Motor {
     M2 = 2,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(eq, eq_int)]
+pub enum Direction {
+    Forward = 1,
+    Backward = -1,
+}
+
 #[pymethods]
 impl RoboClaw {
     #[new]
-    fn new(
+    #[pyo3(signature = (port_name, baud_rate, timeout, retries, address, model=None, capture=None))]
+    pub fn new(
         port_name: &str,
         baud_rate: u32,
         timeout: u32,
         retries: u8,
         address: u8,
+        model: Option<Model>,
+        capture: Option<PathBuf>,
     ) -> Result<Self> {
         let connection: Connection = Connection::new(
             port_name,
             baud_rate,
             Duration::new(0, timeout * 1_000_000),
             retries,
+            capture.as_deref(),
         )
         .context("couldn't make a new connection")?;
 
-        Ok(Self {
-            connection,
+        Ok(Self::with_connection(connection, address, model))
+    }
+
+    /// Replays a capture file as a fake device instead of opening a serial port.
+    #[staticmethod]
+    #[pyo3(signature = (capture, address, retries=1, model=None))]
+    pub fn replay(capture: PathBuf, address: u8, retries: u8, model: Option<Model>) -> Result<Self> {
+        let transport: ReplayTransport = ReplayTransport::load(&capture)?;
+        Ok(Self::with_connection(
+            Connection::with_transport(Box::new(transport), retries),
             address,
-            encoder_value_m1: 0,
-            encoder_value_m2: 0,
-        })
+            model,
+        ))
     }
 
     //--------------------------------[Simple Commands]--------------------------------//
 
     #[pyo3(signature = (motor, speed, address=None))]
-    fn set_speed(&mut self, motor: Motor, speed: i8, address: Option<u8>) -> Result<bool> {
+    pub fn set_speed(&mut self, motor: Motor, speed: i8, address: Option<u8>) -> Result<bool> {
         let command: Commands = match (motor, speed) {
             (Motor::M1, 0..=127) => Commands::M1Forward,
             (Motor::M1, -127..=-1) => Commands::M1Backward,
@@ -89,41 +134,72 @@ impl RoboClaw {
         Ok(true)
     }
 
+    #[pyo3(signature = (forward, turn, address=None))]
+    fn arcade_drive(&mut self, forward: u8, turn: u8, address: Option<u8>) -> Result<bool> {
+        if forward > 127 || turn > 127 {
+            return Ok(false);
+        }
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection
+            .write_sized(address, Commands::MixDrive, &[forward as u32], vec![1])?;
+        self.connection
+            .write_sized(address, Commands::TurnLeftRight, &[turn as u32], vec![1])?;
+        Ok(true)
+    }
+
     //--------------------------------[Encoders]--------------------------------//
 
     #[pyo3(signature = (motor, address=None))]
-    fn read_encoder(&mut self, motor: Motor, address: Option<u8>) -> Result<i64> {
+    pub(crate) fn read_encoder(&mut self, motor: Motor, address: Option<u8>) -> Result<i64> {
         let command: Commands = match motor {
             Motor::M1 => Commands::M1ReadEncoder,
             Motor::M2 => Commands::M2ReadEncoder,
         };
         let address: u8 = address.unwrap_or(self.address);
+        // the count is absolute, unwrapping it doesn't need the over/underflow bits of the status byte
         let read_result: Vec<u32> = self.connection.read(address, command, vec![4, 1])?;
 
-        match motor {
-            Motor::M1 => {
-                let encoder_value: i64 = calculate_encoder(self.encoder_value_m1, read_result);
-                self.encoder_value_m1 = encoder_value;
-                Ok(encoder_value)
-            }
-            Motor::M2 => {
-                let encoder_value: i64 = calculate_encoder(self.encoder_value_m2, read_result);
-                self.encoder_value_m2 = encoder_value;
-                Ok(encoder_value)
-            }
-        }
+        let encoder_value: &mut i64 = match motor {
+            Motor::M1 => &mut self.encoder_value_m1,
+            Motor::M2 => &mut self.encoder_value_m2,
+        };
+        *encoder_value = unwrap_encoder(*encoder_value, read_result[0]);
+        Ok(*encoder_value)
+    }
+
+    #[pyo3(signature = (address=None))]
+    pub fn read_encoders(&mut self, address: Option<u8>) -> Result<(i64, i64)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let read_result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadEncoderCounts, vec![4, 4])?;
+
+        self.encoder_value_m1 = unwrap_encoder(self.encoder_value_m1, read_result[0]);
+        self.encoder_value_m2 = unwrap_encoder(self.encoder_value_m2, read_result[1]);
+        Ok((self.encoder_value_m1, self.encoder_value_m2))
+    }
+
+    #[pyo3(signature = (address=None))]
+    pub fn read_speeds(&mut self, address: Option<u8>) -> Result<(i64, i64)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let read_result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadRawMotorSpeeds, vec![4, 4])?;
+        Ok((read_result[0] as i32 as i64, read_result[1] as i32 as i64))
     }
 
     #[pyo3(signature = (address=None))]
-    fn reset_encoders(&mut self, address: Option<u8>) -> Result<bool> {
+    pub fn reset_encoders(&mut self, address: Option<u8>) -> Result<bool> {
         let address: u8 = address.unwrap_or(self.address);
         self.connection
             .write(address, Commands::ResetEncoders, &[])?;
+        self.encoder_value_m1 = 0;
+        self.encoder_value_m2 = 0;
         Ok(true)
     }
 
     #[pyo3(signature = (motor, encoder_value, address=None))]
-    fn set_encoder(
+    pub(crate) fn set_encoder(
         &mut self,
         motor: Motor,
         encoder_value: i32,
@@ -135,7 +211,12 @@ impl RoboClaw {
         };
         let address: u8 = address.unwrap_or(self.address);
         self.connection
-            .write(address, command, &[encoder_value as u32])?;
+            .write_sized(address, command, &[encoder_value as u32], vec![4])?;
+        // reads are unwrapped relative to the accumulated value, so it has to follow the new count
+        match motor {
+            Motor::M1 => self.encoder_value_m1 = encoder_value as i64,
+            Motor::M2 => self.encoder_value_m2 = encoder_value as i64,
+        }
         Ok(true)
     }
 
@@ -212,10 +293,95 @@ impl RoboClaw {
         })
     }
 
+    #[pyo3(signature = (address=None))]
+    pub(crate) fn read_speed_errors(&mut self, address: Option<u8>) -> Result<(i64, i64)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let read_result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadSpeedErrors, vec![4, 4])?;
+        Ok((read_result[0] as i32 as i64, read_result[1] as i32 as i64))
+    }
+
+    #[pyo3(signature = (address=None))]
+    pub(crate) fn read_position_errors(&mut self, address: Option<u8>) -> Result<(i64, i64)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let read_result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadPositionErrors, vec![4, 4])?;
+        Ok((read_result[0] as i32 as i64, read_result[1] as i32 as i64))
+    }
+
+    #[pyo3(signature = (m1_limit, m2_limit, address=None))]
+    pub fn set_speed_error_limits(&mut self, m1_limit: u32, m2_limit: u32, address: Option<u8>) -> Result<bool> {
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            Commands::SetSpeedErrorLimits,
+            &[m1_limit, m2_limit],
+            vec![4, 4],
+        )?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (address=None))]
+    pub fn read_speed_error_limits(&mut self, address: Option<u8>) -> Result<(u32, u32)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let read_result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadSpeedErrorLimits, vec![4, 4])?;
+        Ok((read_result[0], read_result[1]))
+    }
+
+    #[pyo3(signature = (m1_limit, m2_limit, address=None))]
+    pub fn set_position_error_limits(&mut self, m1_limit: u32, m2_limit: u32, address: Option<u8>) -> Result<bool> {
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            Commands::SetPositionErrorLimits,
+            &[m1_limit, m2_limit],
+            vec![4, 4],
+        )?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (address=None))]
+    pub fn read_position_error_limits(&mut self, address: Option<u8>) -> Result<(u32, u32)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let read_result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadPositionErrorLimits, vec![4, 4])?;
+        Ok((read_result[0], read_result[1]))
+    }
+
+    #[pyo3(signature = (motor, address=None))]
+    pub fn read_encoder_mode(&mut self, motor: Motor, address: Option<u8>) -> Result<EncoderMode> {
+        let address: u8 = address.unwrap_or(self.address);
+        let read_result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadEncoderModes, vec![1, 1])?;
+
+        Ok(match motor {
+            Motor::M1 => EncoderMode::from_bits(read_result[0] as u8),
+            Motor::M2 => EncoderMode::from_bits(read_result[1] as u8),
+        })
+    }
+
+    #[pyo3(signature = (motor, mode, address=None))]
+    pub fn set_encoder_mode(&mut self, motor: Motor, mode: EncoderMode, address: Option<u8>) -> Result<bool> {
+        let command: Commands = match motor {
+            Motor::M1 => Commands::M1SetEncoderMode,
+            Motor::M2 => Commands::M2SetEncoderMode,
+        };
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection
+            .write_sized(address, command, &[mode.bits() as u32], vec![1])?;
+        Ok(true)
+    }
+
     //-----------------------------[Advanced Motor Controls]--------------------------------//
 
     #[pyo3(signature = (motor, qpps, proportional, integral, derivative, address=None))]
-    fn set_velocity_pid(
+    pub fn set_velocity_pid(
         &mut self,
         motor: Motor,
         qpps: i32,
@@ -229,7 +395,7 @@ impl RoboClaw {
             Motor::M2 => Commands::M2SetVelocityPIDConst,
         };
         let address: u8 = address.unwrap_or(self.address);
-        self.connection.write(
+        self.connection.write_sized(
             address,
             command,
             &[
@@ -238,37 +404,288 @@ impl RoboClaw {
                 integral as u32,
                 qpps as u32,
             ],
+            vec![4, 4, 4, 4],
+        )?;
+        Ok(true)
+    }
+
+    /// Returns (qpps, proportional, integral, derivative) in the order of `set_velocity_pid`.
+    #[pyo3(signature = (motor, address=None))]
+    pub fn read_velocity_pid(&mut self, motor: Motor, address: Option<u8>) -> Result<(i32, i32, i32, i32)> {
+        let command: Commands = match motor {
+            Motor::M1 => Commands::M1ReadVelocityPIDConsts,
+            Motor::M2 => Commands::M2ReadVelocityPIDConsts,
+        };
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> = self.connection.read(address, command, vec![4, 4, 4, 4])?;
+        Ok((result[3] as i32, result[0] as i32, result[1] as i32, result[2] as i32))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (motor, proportional, integral, derivative, max_integral, deadzone, min_position, max_position, address=None))]
+    pub fn set_position_pid(
+        &mut self,
+        motor: Motor,
+        proportional: i32,
+        integral: i32,
+        derivative: i32,
+        max_integral: u32,
+        deadzone: u32,
+        min_position: i32,
+        max_position: i32,
+        address: Option<u8>,
+    ) -> Result<bool> {
+        let command: Commands = match motor {
+            Motor::M1 => Commands::M1SetPositionPIDConsts,
+            Motor::M2 => Commands::M2SetPositionPIDConsts,
+        };
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            command,
+            &[
+                derivative as u32,
+                proportional as u32,
+                integral as u32,
+                max_integral,
+                deadzone,
+                min_position as u32,
+                max_position as u32,
+            ],
+            vec![4; 7],
         )?;
         Ok(true)
     }
 
+    /// Returns (proportional, integral, derivative, max_integral, deadzone, min_position, max_position)
+    /// in the order of `set_position_pid`.
+    #[pyo3(signature = (motor, address=None))]
+    pub fn read_position_pid(
+        &mut self,
+        motor: Motor,
+        address: Option<u8>,
+    ) -> Result<(i32, i32, i32, u32, u32, i32, i32)> {
+        let command: Commands = match motor {
+            Motor::M1 => Commands::M1ReadPositionPIDConsts,
+            Motor::M2 => Commands::M2ReadPositionPIDConsts,
+        };
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> = self.connection.read(address, command, vec![4; 7])?;
+        Ok((
+            result[0] as i32,
+            result[1] as i32,
+            result[2] as i32,
+            result[3],
+            result[4],
+            result[5] as i32,
+            result[6] as i32,
+        ))
+    }
+
     #[pyo3(signature = (motor, duty, address=None))]
-    fn set_speed_duty(&mut self, motor: Motor, duty: i16, address: Option<u8>) -> Result<bool> {
+    pub fn set_speed_duty(&mut self, motor: Motor, duty: i16, address: Option<u8>) -> Result<bool> {
         let command: Commands = match motor {
             Motor::M1 => Commands::M1DriveSignedDutyCycle,
             Motor::M2 => Commands::M2DriveSignedDutyCycle,
         };
         let address: u8 = address.unwrap_or(self.address);
         self.connection
-            .write(address, command, &[duty as i32 as u32])?;
+            .write_sized(address, command, &[duty as u16 as u32], vec![2])?;
         Ok(true)
     }
 
     #[pyo3(signature = (duty, address=None))]
-    fn drive_duty(&mut self, duty: i16, address: Option<u8>) -> Result<bool> {
+    pub(crate) fn drive_duty(&mut self, duty: i16, address: Option<u8>) -> Result<bool> {
         let address: u8 = address.unwrap_or(self.address);
-        self.connection.write(
+        self.connection.write_sized(
             address,
             Commands::MixDriveSignedDutyCycle,
-            &[duty as i32 as u32],
+            &[duty as u16 as u32, duty as u16 as u32],
+            vec![2, 2],
         )?;
         Ok(true)
     }
 
+    #[pyo3(signature = (m1_speed, m2_speed, address=None))]
+    pub fn drive_speed(&mut self, m1_speed: i32, m2_speed: i32, address: Option<u8>) -> Result<bool> {
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            Commands::MixDriveSignedSpeed,
+            &[m1_speed as u32, m2_speed as u32],
+            vec![4, 4],
+        )?;
+        Ok(true)
+    }
+
+    /// Sets the speed of both motors in a single packet, so both wheels change speed at the same time.
+    #[pyo3(signature = (m1_speed, m2_speed, accel, distances=None, buffered=false, address=None))]
+    fn drive_both(
+        &mut self,
+        m1_speed: i32,
+        m2_speed: i32,
+        accel: u32,
+        distances: Option<(u32, u32)>,
+        buffered: bool,
+        address: Option<u8>,
+    ) -> Result<bool> {
+        let address: u8 = address.unwrap_or(self.address);
+        match distances {
+            Some((m1_distance, m2_distance)) => self.connection.write_sized(
+                address,
+                Commands::MixDriveIndividualSignedSpeedAccelDistance,
+                &[
+                    accel,
+                    m1_speed as u32,
+                    m1_distance,
+                    accel,
+                    m2_speed as u32,
+                    m2_distance,
+                    !buffered as u32,
+                ],
+                vec![4, 4, 4, 4, 4, 4, 1],
+            )?,
+            None if buffered => {
+                return Err(anyhow!("buffered commands need distances"));
+            }
+            None => self.connection.write_sized(
+                address,
+                Commands::MixDriveIndividualSignedSpeedAccel,
+                &[accel, m1_speed as u32, accel, m2_speed as u32],
+                vec![4, 4, 4, 4],
+            )?,
+        }
+        Ok(true)
+    }
+
+    #[pyo3(signature = (motor, duty, accel, address=None))]
+    fn set_duty_accel(&mut self, motor: Motor, duty: i16, accel: u32, address: Option<u8>) -> Result<bool> {
+        check_duty_accel(duty, accel)?;
+        let command: Commands = match motor {
+            Motor::M1 => Commands::M1DriveSignedDutyAccel,
+            Motor::M2 => Commands::M2DriveSignedDutyAccel,
+        };
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            command,
+            &[duty as u16 as u32, accel],
+            vec![2, 4],
+        )?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (duty, accel, address=None))]
+    fn drive_duty_accel(&mut self, duty: i16, accel: u32, address: Option<u8>) -> Result<bool> {
+        check_duty_accel(duty, accel)?;
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            Commands::MixDriveSignedDutyAccel,
+            &[duty as u16 as u32, accel, duty as u16 as u32, accel],
+            vec![2, 4, 2, 4],
+        )?;
+        Ok(true)
+    }
+
+    /// Without `min_current` the minimum current limit set on the controller is kept.
+    #[pyo3(signature = (motor, current, min_current=None, address=None))]
+    pub fn set_max_current(
+        &mut self,
+        motor: Motor,
+        current: f32,
+        min_current: Option<f32>,
+        address: Option<u8>,
+    ) -> Result<bool> {
+        if !(0.0..=MAX_CURRENT).contains(&current) {
+            return Err(anyhow!("invalid value: {} needs to be between 0 and {} amps", current, MAX_CURRENT));
+        }
+        if let Some(min_current) = min_current
+            && !(0.0..=current).contains(&min_current)
+        {
+            return Err(anyhow!("invalid value: {} needs to be between 0 and {} amps", min_current, current));
+        }
+        if let Some(model) = self.model
+            && current > model.peak_current()
+        {
+            return Err(anyhow!("{} amps is above the peak current of {:?} ({} amps)", current, model, model.peak_current()));
+        }
+
+        let command: Commands = match motor {
+            Motor::M1 => Commands::M1SetMaxCurrent,
+            Motor::M2 => Commands::M2SetMaxCurrent,
+        };
+        let min_current: u32 = match min_current {
+            Some(min_current) => (min_current * 100.0).round() as u32,
+            None => self.read_current_limits(motor, address)?[1],
+        };
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            command,
+            &[(current * 100.0).round() as u32, min_current],
+            vec![4, 4],
+        )?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (motor, address=None))]
+    pub fn read_max_current(&mut self, motor: Motor, address: Option<u8>) -> Result<f32> {
+        Ok(self.read_current_limits(motor, address)?[0] as f32 / 100.0)
+    }
+
+    #[pyo3(signature = (motor, address=None))]
+    pub fn read_min_current(&mut self, motor: Motor, address: Option<u8>) -> Result<f32> {
+        Ok(self.read_current_limits(motor, address)?[1] as f32 / 100.0)
+    }
+
+    #[pyo3(signature = (mode, address=None))]
+    pub fn set_pwm_mode(&mut self, mode: PwmMode, address: Option<u8>) -> Result<bool> {
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection
+            .write_sized(address, Commands::SetPWMMode, &[mode as u32], vec![1])?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (address=None))]
+    pub fn read_pwm_mode(&mut self, address: Option<u8>) -> Result<PwmMode> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadPWMMode, vec![1])?;
+        PwmMode::from_byte(result[0] as u8)
+    }
+
+    #[pyo3(signature = (reverse, forward, address=None))]
+    pub fn set_deadband(&mut self, reverse: f32, forward: f32, address: Option<u8>) -> Result<bool> {
+        if !(0.0..=MAX_DEADBAND).contains(&reverse) || !(0.0..=MAX_DEADBAND).contains(&forward) {
+            return Err(anyhow!("invalid value: {} / {} need to be between 0 and {} percent", reverse, forward, MAX_DEADBAND));
+        }
+        let reverse: u8 = to_fixed(reverse, 10.0)?;
+        let forward: u8 = to_fixed(forward, 10.0)?;
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            Commands::SetDeadBandRCAnalog,
+            &[reverse as u32, forward as u32],
+            vec![1, 1],
+        )?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (address=None))]
+    pub fn read_deadband(&mut self, address: Option<u8>) -> Result<(f32, f32)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadDeadBandRCAnalog, vec![1, 1])?;
+        Ok((result[0] as f32 / 10.0, result[1] as f32 / 10.0))
+    }
+
     //--------------------------------[Advanced Commands]--------------------------------//
 
     #[pyo3(signature = (timeout, address=None))]
-    fn set_serial_timeout(&mut self, timeout: u8, address: Option<u8>) -> Result<bool> {
+    pub fn set_serial_timeout(&mut self, timeout: u8, address: Option<u8>) -> Result<bool> {
         let address: u8 = address.unwrap_or(self.address);
         self.connection
             .write(address, Commands::SetSerialTimeout, &[timeout as u32])?;
@@ -276,11 +693,877 @@ impl RoboClaw {
     }
 
     #[pyo3(signature = (address=None))]
-    fn read_serial_timeout(&mut self, address: Option<u8>) -> Result<u8> {
+    pub fn read_status(&mut self, address: Option<u8>) -> Result<Status> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadStatus, vec![4])?;
+        Ok(Status::new(result[0]))
+    }
+
+    #[pyo3(signature = (address=None))]
+    pub fn read_version(&mut self, address: Option<u8>) -> Result<String> {
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection
+            .read_string(address, Commands::ReadFirmwareVersion, MAX_VERSION_LENGTH)
+    }
+
+    /// Stores the current settings in the EEPROM, so they are kept after a power cycle.
+    #[pyo3(signature = (address=None))]
+    pub fn write_settings_to_eeprom(&mut self, address: Option<u8>) -> Result<bool> {
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection
+            .write_sized(address, Commands::EEPROMWriteSettings, &[], vec![])?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (address=None))]
+    pub fn read_serial_timeout(&mut self, address: Option<u8>) -> Result<u8> {
         let address: u8 = address.unwrap_or(self.address);
         let result: Vec<u32> =
             self.connection
                 .read(address, Commands::ReadSerialTimeout, vec![1])?;
         Ok(result[0] as u8)
     }
+
+    //--------------------------------[Units]--------------------------------//
+
+    #[pyo3(signature = (motor, units=None))]
+    fn set_units(&mut self, motor: Motor, units: Option<MotorUnits>) {
+        match motor {
+            Motor::M1 => self.units_m1 = units,
+            Motor::M2 => self.units_m2 = units,
+        }
+    }
+
+    fn get_units(&self, motor: Motor) -> Option<MotorUnits> {
+        match motor {
+            Motor::M1 => self.units_m1,
+            Motor::M2 => self.units_m2,
+        }
+    }
+
+    #[pyo3(signature = (motor, unit, address=None))]
+    pub(crate) fn read_position(&mut self, motor: Motor, unit: Unit, address: Option<u8>) -> Result<f64> {
+        let units: MotorUnits = self.motor_units(motor, unit)?;
+        let counts: i64 = self.read_encoder(motor, address)?;
+        units.convert_from_counts(counts as f64, unit)
+    }
+
+    #[pyo3(signature = (motor, position, unit, address=None))]
+    pub(crate) fn set_position(&mut self, motor: Motor, position: f64, unit: Unit, address: Option<u8>) -> Result<bool> {
+        let units: MotorUnits = self.motor_units(motor, unit)?;
+        let counts: i32 = to_counts(units.convert_to_counts(position, unit)?)?;
+        self.set_encoder(motor, counts, address)
+    }
+
+    #[pyo3(signature = (motor, unit, address=None))]
+    fn read_velocity(&mut self, motor: Motor, unit: Unit, address: Option<u8>) -> Result<f64> {
+        let units: MotorUnits = self.motor_units(motor, unit)?;
+        let counts: i64 = self.read_encoder_speed(motor, address)?;
+        units.convert_speed_from_counts(counts as f64, unit)
+    }
+
+    #[pyo3(signature = (motor, speed, unit, accel=None, address=None))]
+    pub fn set_velocity(
+        &mut self,
+        motor: Motor,
+        speed: f64,
+        unit: Unit,
+        accel: Option<f64>,
+        address: Option<u8>,
+    ) -> Result<bool> {
+        let units: MotorUnits = self.motor_units(motor, unit)?;
+        let speed: i32 = to_counts(units.convert_speed_to_counts(speed, unit)?)?;
+        let address: u8 = address.unwrap_or(self.address);
+
+        match accel {
+            Some(accel) => {
+                let accel: u32 = to_counts(units.convert_speed_to_counts(accel, unit)?)?;
+                let command: Commands = match motor {
+                    Motor::M1 => Commands::M1DriveSignedSpeedAccel,
+                    Motor::M2 => Commands::M2DriveSignedSpeedAccel,
+                };
+                self.connection
+                    .write_sized(address, command, &[accel, speed as u32], vec![4, 4])?;
+            }
+            None => {
+                let command: Commands = match motor {
+                    Motor::M1 => Commands::M1DriveSignedSpeed,
+                    Motor::M2 => Commands::M2DriveSignedSpeed,
+                };
+                self.connection
+                    .write_sized(address, command, &[speed as u32], vec![4])?;
+            }
+        }
+        Ok(true)
+    }
+
+    #[pyo3(signature = (motor, speed, distance, unit, accel=None, buffered=false, address=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn move_distance(
+        &mut self,
+        motor: Motor,
+        speed: f64,
+        distance: f64,
+        unit: Unit,
+        accel: Option<f64>,
+        buffered: bool,
+        address: Option<u8>,
+    ) -> Result<bool> {
+        let units: MotorUnits = self.motor_units(motor, unit)?;
+        let speed: i32 = to_counts(units.convert_speed_to_counts(speed, unit)?)?;
+        let distance: u32 = to_counts(units.convert_to_counts(distance, unit)?)?;
+        let address: u8 = address.unwrap_or(self.address);
+
+        match accel {
+            Some(accel) => {
+                let accel: u32 = to_counts(units.convert_speed_to_counts(accel, unit)?)?;
+                let command: Commands = match motor {
+                    Motor::M1 => Commands::M1DriveSignedSpeedAccelDistanceBuffered,
+                    Motor::M2 => Commands::M2DriveSignedSpeedAccelDistanceBuffered,
+                };
+                self.connection.write_sized(
+                    address,
+                    command,
+                    &[accel, speed as u32, distance, !buffered as u32],
+                    vec![4, 4, 4, 1],
+                )?;
+            }
+            None => {
+                let command: Commands = match motor {
+                    Motor::M1 => Commands::M1DriveSignedSpeedDistanceBuffered,
+                    Motor::M2 => Commands::M2DriveSignedSpeedDistanceBuffered,
+                };
+                self.connection.write_sized(
+                    address,
+                    command,
+                    &[speed as u32, distance, !buffered as u32],
+                    vec![4, 4, 1],
+                )?;
+            }
+        }
+        Ok(true)
+    }
+
+    #[pyo3(signature = (motor, position, speed, accel, deccel, unit, buffered=false, address=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn move_to_position(
+        &mut self,
+        motor: Motor,
+        position: f64,
+        speed: f64,
+        accel: f64,
+        deccel: f64,
+        unit: Unit,
+        buffered: bool,
+        address: Option<u8>,
+    ) -> Result<bool> {
+        let units: MotorUnits = self.motor_units(motor, unit)?;
+        let position: i32 = to_counts(units.convert_to_counts(position, unit)?)?;
+        let speed: u32 = to_counts(units.convert_speed_to_counts(speed, unit)?)?;
+        let accel: u32 = to_counts(units.convert_speed_to_counts(accel, unit)?)?;
+        let deccel: u32 = to_counts(units.convert_speed_to_counts(deccel, unit)?)?;
+
+        let command: Commands = match motor {
+            Motor::M1 => Commands::M1DriveSpeedAccelDeccelPosition,
+            Motor::M2 => Commands::M2DriveSpeedAccelDeccelPosition,
+        };
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            command,
+            &[accel, speed, deccel, position as u32, !buffered as u32],
+            vec![4, 4, 4, 4, 1],
+        )?;
+        Ok(true)
+    }
+
+    //--------------------------------[Trajectories]--------------------------------//
+
+    #[pyo3(signature = (address=None))]
+    fn read_buffer_length(&mut self, address: Option<u8>) -> Result<(u8, u8)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadBufferLength, vec![1, 1])?;
+        Ok((result[0] as u8, result[1] as u8))
+    }
+
+    /// Sends the segments as buffered commands, keeping at most `depth` commands in the buffer.
+    /// Without a motor both motors run the same segments. Returns the number of commands sent.
+    /// Both motors are stopped if sending fails or the wait is interrupted.
+    #[pyo3(signature = (segments, motor=None, depth=8, poll_interval=0.01, wait=true, address=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn execute_trajectory(
+        &mut self,
+        py: Python<'_>,
+        segments: Vec<Segment>,
+        motor: Option<Motor>,
+        depth: u8,
+        poll_interval: f64,
+        wait: bool,
+        address: Option<u8>,
+    ) -> Result<usize> {
+        if depth == 0 {
+            return Err(anyhow!("invalid value: depth needs to be at least 1"));
+        }
+        let poll_interval: Duration = Duration::try_from_secs_f64(poll_interval)?;
+
+        let result: Result<usize> = self.feed_trajectory(py, &segments, motor, depth, poll_interval, wait, address);
+        if result.is_err() {
+            // the commands already in the buffer would keep running
+            let _ = self.drive_duty(0, address);
+        }
+        result
+    }
+
+    //--------------------------------[Communication]--------------------------------//
+
+    /// Packets sent and failed since the connection was opened or the counters were reset.
+    pub fn comm_stats(&self) -> CommStats {
+        self.connection.stats()
+    }
+
+    pub fn reset_comm_stats(&mut self) {
+        self.connection.reset_stats();
+    }
+
+    //--------------------------------[Telemetry]--------------------------------//
+
+    #[pyo3(signature = (address=None))]
+    pub fn read_currents(&mut self, address: Option<u8>) -> Result<(f32, f32)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadMotorCurrents, vec![2, 2])?;
+        Ok((
+            result[0] as u16 as i16 as f32 / 100.0,
+            result[1] as u16 as i16 as f32 / 100.0,
+        ))
+    }
+
+    #[pyo3(signature = (address=None))]
+    pub fn read_pwms(&mut self, address: Option<u8>) -> Result<(i16, i16)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadMotorPWMs, vec![2, 2])?;
+        Ok((result[0] as u16 as i16, result[1] as u16 as i16))
+    }
+
+    #[pyo3(signature = (sensor=1, address=None))]
+    pub fn read_temperature(&mut self, sensor: u8, address: Option<u8>) -> Result<f32> {
+        let command: Commands = match sensor {
+            1 => Commands::ReadTemperature1,
+            2 => Commands::ReadTemperature2,
+            _ => return Err(anyhow!("invalid value: {} needs to be 1 or 2", sensor)),
+        };
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> = self.connection.read(address, command, vec![2])?;
+        Ok(result[0] as u16 as i16 as f32 / 10.0)
+    }
+
+    //--------------------------------[Defaults]--------------------------------//
+
+    #[pyo3(signature = (address=None))]
+    fn read_defaults(&mut self, address: Option<u8>) -> Result<Defaults> {
+        let address: u8 = address.unwrap_or(self.address);
+        let accel: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadDefaultDutyCycleAccel, vec![4, 4])?;
+        let speed: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadDefaultSpeedSettings, vec![2, 2])?;
+
+        Ok(Defaults {
+            m1_duty_accel: accel[0],
+            m2_duty_accel: accel[1],
+            m1_speed: speed[0] as u16,
+            m2_speed: speed[1] as u16,
+        })
+    }
+
+    #[pyo3(signature = (defaults, address=None))]
+    fn write_defaults(&mut self, defaults: Defaults, address: Option<u8>) -> Result<bool> {
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            Commands::M1SetDefaultDutyCycle,
+            &[defaults.m1_duty_accel],
+            vec![4],
+        )?;
+        self.connection.write_sized(
+            address,
+            Commands::M2SetDefaultDutyCycle,
+            &[defaults.m2_duty_accel],
+            vec![4],
+        )?;
+        self.connection.write_sized(
+            address,
+            Commands::M1SetDefaultSpeed,
+            &[defaults.m1_speed as u32],
+            vec![2],
+        )?;
+        self.connection.write_sized(
+            address,
+            Commands::M2SetDefaultSpeed,
+            &[defaults.m2_speed as u32],
+            vec![2],
+        )?;
+        Ok(true)
+    }
+
+    //--------------------------------[Standard Config]--------------------------------//
+
+    #[pyo3(signature = (address=None))]
+    pub fn read_standard_config(&mut self, address: Option<u8>) -> Result<StandardConfig> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadStandardConfigSettings, vec![2])?;
+        Ok(StandardConfig::new(result[0] as u16))
+    }
+
+    /// Writes the config and, if it stays in packet serial mode, switches the connection over to the new
+    /// baud rate and packet address and verifies the RoboClaw answers there.
+    #[pyo3(signature = (config, reconnect=true, address=None))]
+    pub fn write_standard_config(
+        &mut self,
+        config: StandardConfig,
+        reconnect: bool,
+        address: Option<u8>,
+    ) -> Result<bool> {
+        let uses_default: bool = address.is_none() || address == Some(self.address);
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            Commands::SetStandardConfigSettings,
+            &[config.bits as u32],
+            vec![2],
+        )?;
+        if !reconnect || config.mode() != ControlMode::PacketSerial {
+            return Ok(true);
+        }
+
+        let previous_baud_rate: u32 = self.connection.baud_rate()?;
+        self.connection.set_baud_rate(config.baud_rate())?;
+
+        let verified: Result<StandardConfig> = self.read_standard_config(Some(config.packet_address()));
+        match verified {
+            Result::Ok(read_back) if read_back == config => {
+                if uses_default {
+                    self.address = config.packet_address();
+                }
+                Ok(true)
+            }
+            _ => {
+                self.connection.set_baud_rate(previous_baud_rate)?;
+                Err(anyhow!(
+                    "RoboClaw didn't answer at address {} with {} baud after writing the config",
+                    config.packet_address(),
+                    config.baud_rate()
+                ))
+            }
+        }
+    }
+
+    //--------------------------------[Battery]--------------------------------//
+
+    #[pyo3(signature = (address=None))]
+    pub fn read_main_battery_voltage(&mut self, address: Option<u8>) -> Result<f32> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadMainBatVoltage, vec![2])?;
+        Ok(result[0] as f32 / 10.0)
+    }
+
+    #[pyo3(signature = (address=None))]
+    pub fn read_logic_battery_voltage(&mut self, address: Option<u8>) -> Result<f32> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadLogicBatVoltage, vec![2])?;
+        Ok(result[0] as f32 / 10.0)
+    }
+
+    #[pyo3(signature = (main, logic, address=None))]
+    fn set_battery_voltage_offsets(&mut self, main: f32, logic: f32, address: Option<u8>) -> Result<bool> {
+        let main: i8 = to_fixed(main, 10.0)?;
+        let logic: i8 = to_fixed(logic, 10.0)?;
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            Commands::SetBatteryVoltageOffsets,
+            &[main as u8 as u32, logic as u8 as u32],
+            vec![1, 1],
+        )?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (address=None))]
+    fn read_battery_voltage_offsets(&mut self, address: Option<u8>) -> Result<(f32, f32)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadBatteryVoltageOffsets, vec![1, 1])?;
+        Ok((
+            result[0] as u8 as i8 as f32 / 10.0,
+            result[1] as u8 as i8 as f32 / 10.0,
+        ))
+    }
+
+    #[pyo3(signature = (m1, m2, address=None))]
+    fn set_current_blanking(&mut self, m1: f32, m2: f32, address: Option<u8>) -> Result<bool> {
+        if !(0.0..=100.0).contains(&m1) || !(0.0..=100.0).contains(&m2) {
+            return Err(anyhow!("invalid value: {} / {} need to be between 0 and 100 percent", m1, m2));
+        }
+        let m1: u16 = to_fixed(m1, 10.0)?;
+        let m2: u16 = to_fixed(m2, 10.0)?;
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            Commands::SetCurrentBlankingPercentages,
+            &[m1 as u32, m2 as u32],
+            vec![2, 2],
+        )?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (address=None))]
+    fn read_current_blanking(&mut self, address: Option<u8>) -> Result<(f32, f32)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadCurrentBlankinPercentages, vec![2, 2])?;
+        Ok((result[0] as f32 / 10.0, result[1] as f32 / 10.0))
+    }
+
+    /// Compares the main battery reading to a measured reference voltage and returns the offset that corrects it.
+    #[pyo3(signature = (reference_voltage, samples=10, apply=true, address=None))]
+    fn calibrate_main_battery_offset(
+        &mut self,
+        reference_voltage: f32,
+        samples: u32,
+        apply: bool,
+        address: Option<u8>,
+    ) -> Result<f32> {
+        if samples == 0 {
+            return Err(anyhow!("invalid value: samples needs to be at least 1"));
+        }
+
+        let (main_offset, logic_offset): (f32, f32) = self.read_battery_voltage_offsets(address)?;
+        let mut sum: f32 = 0.0;
+        for _ in 0..samples {
+            sum += self.read_main_battery_voltage(address)?;
+        }
+        let measured: f32 = sum / samples as f32;
+
+        let offset: f32 = (main_offset + reference_voltage - measured).clamp(-12.8, 12.7);
+        let offset: f32 = (offset * 10.0).round() / 10.0;
+        if apply {
+            self.set_battery_voltage_offsets(offset, logic_offset, address)?;
+        }
+        Ok(offset)
+    }
+
+    //--------------------------------[Homing]--------------------------------//
+
+    #[pyo3(signature = (motor, percentage, timeout, address=None))]
+    fn set_auto_home(
+        &mut self,
+        motor: Motor,
+        percentage: u16,
+        timeout: u32,
+        address: Option<u8>,
+    ) -> Result<bool> {
+        let command: Commands = match motor {
+            Motor::M1 => Commands::M1SetAutoHomeDutySpeedTimeout,
+            Motor::M2 => Commands::M2SetAutoHomeDutySpeedTimeout,
+        };
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            command,
+            &[percentage as u32, timeout],
+            vec![2, 4],
+        )?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (motor, address=None))]
+    fn read_auto_home(&mut self, motor: Motor, address: Option<u8>) -> Result<(u16, u32)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadAutoHomeSettings, vec![2, 2, 4, 4])?;
+
+        Ok(match motor {
+            Motor::M1 => (result[0] as u16, result[2]),
+            Motor::M2 => (result[1] as u16, result[3]),
+        })
+    }
+
+    /// Drives the motor with the given duty until its home switch triggers, then zeroes the encoder.
+    /// Returns false if the home switch didn't trigger before the timeout (in seconds).
+    #[pyo3(signature = (motor, direction, duty, timeout, address=None))]
+    pub(crate) fn home(
+        &mut self,
+        py: Python<'_>,
+        motor: Motor,
+        direction: Direction,
+        duty: u16,
+        timeout: f64,
+        address: Option<u8>,
+    ) -> Result<bool> {
+        let flag: StatusFlag = match motor {
+            Motor::M1 => StatusFlag::M1Home,
+            Motor::M2 => StatusFlag::M2Home,
+        };
+        let timeout: Duration = Duration::try_from_secs_f64(timeout)?;
+        let duty: i16 = duty.min(i16::MAX as u16) as i16 * direction as i16;
+
+        let homed: Result<bool> = self.wait_for_status(py, motor, duty, flag, timeout, address);
+        self.set_speed_duty(motor, 0, address)?;
+        if !homed? {
+            return Ok(false);
+        }
+
+        self.set_encoder(motor, 0, address)
+    }
+
+    //--------------------------------[Pins and Outputs]--------------------------------//
+
+    #[pyo3(signature = (s3, s4, s5, address=None))]
+    pub fn set_pin_modes(
+        &mut self,
+        s3: PinMode,
+        s4: PinMode,
+        s5: PinMode,
+        address: Option<u8>,
+    ) -> Result<bool> {
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            Commands::SetS3S4S5Modes,
+            &[s3 as u32, s4 as u32, s5 as u32],
+            vec![1, 1, 1],
+        )?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (address=None))]
+    pub fn read_pin_modes(&mut self, address: Option<u8>) -> Result<(PinMode, PinMode, PinMode)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadS3S4S5Modes, vec![1, 1, 1])?;
+        Ok((
+            PinMode::from_byte(result[0] as u8)?,
+            PinMode::from_byte(result[1] as u8)?,
+            PinMode::from_byte(result[2] as u8)?,
+        ))
+    }
+
+    #[pyo3(signature = (ctrl1, ctrl2, address=None))]
+    fn set_ctrl_modes(&mut self, ctrl1: CtrlMode, ctrl2: CtrlMode, address: Option<u8>) -> Result<bool> {
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            Commands::SetCTRLModes,
+            &[ctrl1 as u32, ctrl2 as u32],
+            vec![1, 1],
+        )?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (address=None))]
+    fn read_ctrl_modes(&mut self, address: Option<u8>) -> Result<(CtrlMode, CtrlMode)> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadCTRLModes, vec![1, 1])?;
+        Ok((
+            CtrlMode::from_byte(result[0] as u8)?,
+            CtrlMode::from_byte(result[1] as u8)?,
+        ))
+    }
+
+    #[pyo3(signature = (ctrl, value, address=None))]
+    fn set_ctrl(&mut self, ctrl: Ctrl, value: u16, address: Option<u8>) -> Result<bool> {
+        let command: Commands = match ctrl {
+            Ctrl::CTRL1 => Commands::SetCTRL1,
+            Ctrl::CTRL2 => Commands::SetCTRL2,
+        };
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection
+            .write_sized(address, command, &[value as u32], vec![2])?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (ctrl, address=None))]
+    fn read_ctrl(&mut self, ctrl: Ctrl, address: Option<u8>) -> Result<u16> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> =
+            self.connection
+                .read(address, Commands::ReadCTRLs, vec![2, 2])?;
+        Ok(match ctrl {
+            Ctrl::CTRL1 => result[0] as u16,
+            Ctrl::CTRL2 => result[1] as u16,
+        })
+    }
+
+    //--------------------------------[User Memory]--------------------------------//
+
+    #[pyo3(signature = (index, address=None))]
+    fn read_user_word(&mut self, index: u8, address: Option<u8>) -> Result<u16> {
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> = self.connection.read_with(
+            address,
+            Commands::EEPROMReadUserMemoryLocation,
+            &[index as u32],
+            vec![1],
+            vec![2],
+        )?;
+        Ok(result[0] as u16)
+    }
+
+    #[pyo3(signature = (index, value, address=None))]
+    fn write_user_word(&mut self, index: u8, value: u16, address: Option<u8>) -> Result<bool> {
+        let address: u8 = address.unwrap_or(self.address);
+        self.connection.write_sized(
+            address,
+            Commands::EEPROMWriteUserMemoryLocation,
+            &[index as u32, value as u32],
+            vec![1, 2],
+        )?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (address=None))]
+    fn read_user_memory(&mut self, address: Option<u8>) -> Result<BTreeMap<String, UserValue>> {
+        Ok(self.load_user_memory(address)?.entries)
+    }
+
+    #[pyo3(signature = (values, address=None))]
+    fn write_user_memory(
+        &mut self,
+        values: BTreeMap<String, UserValue>,
+        address: Option<u8>,
+    ) -> Result<bool> {
+        self.store_user_memory(&UserMemory { entries: values }, address)?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (key, address=None))]
+    fn get_user_value(&mut self, key: &str, address: Option<u8>) -> Result<Option<UserValue>> {
+        Ok(self.load_user_memory(address)?.entries.remove(key))
+    }
+
+    #[pyo3(signature = (key, value, address=None))]
+    fn set_user_value(&mut self, key: &str, value: UserValue, address: Option<u8>) -> Result<bool> {
+        let mut memory: UserMemory = self.load_user_memory(address)?;
+        memory.entries.insert(key.to_string(), value);
+        self.store_user_memory(&memory, address)?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (key, address=None))]
+    fn remove_user_value(&mut self, key: &str, address: Option<u8>) -> Result<bool> {
+        let mut memory: UserMemory = self.load_user_memory(address)?;
+        if memory.entries.remove(key).is_none() {
+            return Ok(false);
+        }
+        self.store_user_memory(&memory, address)?;
+        Ok(true)
+    }
+
+    #[pyo3(signature = (key, address=None))]
+    fn get_user_int(&mut self, key: &str, address: Option<u8>) -> Result<Option<i64>> {
+        match self.get_user_value(key, address)? {
+            Some(UserValue::Int(value)) => Ok(Some(value)),
+            Some(other) => Err(anyhow!("{} is not an int: {:?}", key, other)),
+            None => Ok(None),
+        }
+    }
+
+    #[pyo3(signature = (key, address=None))]
+    fn get_user_float(&mut self, key: &str, address: Option<u8>) -> Result<Option<f64>> {
+        match self.get_user_value(key, address)? {
+            Some(UserValue::Float(value)) => Ok(Some(value)),
+            Some(UserValue::Int(value)) => Ok(Some(value as f64)),
+            Some(other) => Err(anyhow!("{} is not a float: {:?}", key, other)),
+            None => Ok(None),
+        }
+    }
+
+    #[pyo3(signature = (key, address=None))]
+    fn get_user_str(&mut self, key: &str, address: Option<u8>) -> Result<Option<String>> {
+        match self.get_user_value(key, address)? {
+            Some(UserValue::Str(value)) => Ok(Some(value)),
+            Some(other) => Err(anyhow!("{} is not a str: {:?}", key, other)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl RoboClaw {
+    pub fn with_connection(connection: Connection, address: u8, model: Option<Model>) -> Self {
+        Self {
+            connection,
+            address,
+            encoder_value_m1: 0,
+            encoder_value_m2: 0,
+            model,
+            units_m1: None,
+            units_m2: None,
+        }
+    }
+
+    fn motor_units(&self, motor: Motor, unit: Unit) -> Result<MotorUnits> {
+        match (self.get_units(motor), unit) {
+            (Some(units), _) => Ok(units),
+            (None, Unit::Counts) => Ok(MotorUnits::COUNTS),
+            (None, _) => Err(anyhow!("no units configured for {:?}, use set_units first", motor)),
+        }
+    }
+
+    /// Returns the raw [max, min] current limits in 10mA units.
+    fn read_current_limits(&mut self, motor: Motor, address: Option<u8>) -> Result<[u32; 2]> {
+        let command: Commands = match motor {
+            Motor::M1 => Commands::M1ReadMaxCurrent,
+            Motor::M2 => Commands::M2ReadMaxCurrent,
+        };
+        let address: u8 = address.unwrap_or(self.address);
+        let result: Vec<u32> = self.connection.read(address, command, vec![4, 4])?;
+        Ok([result[0], result[1]])
+    }
+
+    fn queued_commands(&mut self, motor: Option<Motor>, address: Option<u8>) -> Result<u8> {
+        let (m1, m2) = self.read_buffer_length(address)?;
+        let queued = |length: u8| if length == BUFFER_EMPTY { 0 } else { length };
+        Ok(match motor {
+            Some(Motor::M1) => queued(m1),
+            Some(Motor::M2) => queued(m2),
+            None => queued(m1).max(queued(m2)),
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn feed_trajectory(
+        &mut self,
+        py: Python<'_>,
+        segments: &[Segment],
+        motor: Option<Motor>,
+        depth: u8,
+        poll_interval: Duration,
+        wait: bool,
+        address: Option<u8>,
+    ) -> Result<usize> {
+        let mut sent: usize = 0;
+        while sent < segments.len() {
+            let queued: u8 = self.queued_commands(motor, address)?;
+            for _ in queued..depth {
+                let Some(segment) = segments.get(sent) else {
+                    break;
+                };
+                self.send_segment(segment, motor, sent > 0, address)?;
+                sent += 1;
+            }
+            sleep_interruptible(py, poll_interval)?;
+        }
+
+        while wait && self.read_buffer_length(address)? != (BUFFER_EMPTY, BUFFER_EMPTY) {
+            sleep_interruptible(py, poll_interval)?;
+        }
+        Ok(sent)
+    }
+
+    fn send_segment(&mut self, segment: &Segment, motor: Option<Motor>, buffered: bool, address: Option<u8>) -> Result<()> {
+        let address: u8 = address.unwrap_or(self.address);
+        let values: [u32; 3] = [segment.accel, segment.speed as u32, segment.distance];
+        match motor {
+            Some(motor) => {
+                let command: Commands = match motor {
+                    Motor::M1 => Commands::M1DriveSignedSpeedAccelDistanceBuffered,
+                    Motor::M2 => Commands::M2DriveSignedSpeedAccelDistanceBuffered,
+                };
+                self.connection.write_sized(
+                    address,
+                    command,
+                    &[values[0], values[1], values[2], !buffered as u32],
+                    vec![4, 4, 4, 1],
+                )
+            }
+            None => self.connection.write_sized(
+                address,
+                Commands::MixDriveSignedSpeedAccelDistanceBuffered,
+                &[values[0], values[1], values[2], values[1], values[2], !buffered as u32],
+                vec![4, 4, 4, 4, 4, 1],
+            ),
+        }
+    }
+
+    fn wait_for_status(
+        &mut self,
+        py: Python<'_>,
+        motor: Motor,
+        duty: i16,
+        flag: StatusFlag,
+        timeout: Duration,
+        address: Option<u8>,
+    ) -> Result<bool> {
+        let start: Instant = Instant::now();
+        self.set_speed_duty(motor, duty, address)?;
+        while start.elapsed() < timeout {
+            if self.read_status(address)?.contains(flag) {
+                return Ok(true);
+            }
+            sleep_interruptible(py, HOME_POLL_INTERVAL)?;
+        }
+        Ok(false)
+    }
+
+    fn load_user_memory(&mut self, address: Option<u8>) -> Result<UserMemory> {
+        let header: [u16; 2] = [
+            self.read_user_word(0, address)?,
+            self.read_user_word(1, address)?,
+        ];
+        let needed: usize = match UserMemory::words_needed(header) {
+            Some(needed) if needed <= USER_MEMORY_WORDS => needed,
+            _ => return Ok(UserMemory::default()),
+        };
+
+        let mut words: Vec<u16> = header.to_vec();
+        for index in 2..needed {
+            words.push(self.read_user_word(index as u8, address)?);
+        }
+        UserMemory::decode(&words)
+    }
+
+    fn store_user_memory(&mut self, memory: &UserMemory, address: Option<u8>) -> Result<()> {
+        for (index, word) in memory.encode()?.into_iter().enumerate() {
+            self.write_user_word(index as u8, word, address)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sleeps with the GIL released, so other Python threads keep running, then raises a pending
+/// interrupt like Ctrl-C.
+fn sleep_interruptible(py: Python<'_>, duration: Duration) -> Result<()> {
+    py.allow_threads(|| thread::sleep(duration));
+    py.check_signals()?;
+    Ok(())
+}
+
+fn check_duty_accel(duty: i16, accel: u32) -> Result<()> {
+    if duty == i16::MIN {
+        return Err(anyhow!("invalid value: {} needs to be between -32767 and 32767", duty));
+    }
+    if accel > MAX_DUTY_ACCEL {
+        return Err(anyhow!("invalid value: {} needs to be between 0 and {}", accel, MAX_DUTY_ACCEL));
+    }
+    Ok(())
 }
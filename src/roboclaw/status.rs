@@ -0,0 +1,93 @@
+use pyo3::prelude::*;
+
+/// Bits of the status word returned by command 90.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(eq, eq_int)]
+pub enum StatusFlag {
+    EStop = 0x0000_0001,
+    TemperatureError = 0x0000_0002,
+    Temperature2Error = 0x0000_0004,
+    MainVoltageHighError = 0x0000_0008,
+    LogicVoltageHighError = 0x0000_0010,
+    LogicVoltageLowError = 0x0000_0020,
+    M1DriverFaultError = 0x0000_0040,
+    M2DriverFaultError = 0x0000_0080,
+    M1SpeedError = 0x0000_0100,
+    M2SpeedError = 0x0000_0200,
+    M1PositionError = 0x0000_0400,
+    M2PositionError = 0x0000_0800,
+    M1CurrentError = 0x0000_1000,
+    M2CurrentError = 0x0000_2000,
+    M1OverCurrentWarning = 0x0001_0000,
+    M2OverCurrentWarning = 0x0002_0000,
+    MainVoltageHighWarning = 0x0004_0000,
+    MainVoltageLowWarning = 0x0008_0000,
+    TemperatureWarning = 0x0010_0000,
+    Temperature2Warning = 0x0020_0000,
+    M1Home = 0x0040_0000,
+    M2Home = 0x0080_0000,
+    SpeedErrorLimitWarning = 0x0100_0000,
+    PositionErrorLimitWarning = 0x0200_0000,
+}
+
+const ALL_FLAGS: [StatusFlag; 24] = [
+    StatusFlag::EStop,
+    StatusFlag::TemperatureError,
+    StatusFlag::Temperature2Error,
+    StatusFlag::MainVoltageHighError,
+    StatusFlag::LogicVoltageHighError,
+    StatusFlag::LogicVoltageLowError,
+    StatusFlag::M1DriverFaultError,
+    StatusFlag::M2DriverFaultError,
+    StatusFlag::M1SpeedError,
+    StatusFlag::M2SpeedError,
+    StatusFlag::M1PositionError,
+    StatusFlag::M2PositionError,
+    StatusFlag::M1CurrentError,
+    StatusFlag::M2CurrentError,
+    StatusFlag::M1OverCurrentWarning,
+    StatusFlag::M2OverCurrentWarning,
+    StatusFlag::MainVoltageHighWarning,
+    StatusFlag::MainVoltageLowWarning,
+    StatusFlag::TemperatureWarning,
+    StatusFlag::Temperature2Warning,
+    StatusFlag::M1Home,
+    StatusFlag::M2Home,
+    StatusFlag::SpeedErrorLimitWarning,
+    StatusFlag::PositionErrorLimitWarning,
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[pyclass(eq)]
+pub struct Status {
+    #[pyo3(get)]
+    pub bits: u32,
+}
+
+#[pymethods]
+impl Status {
+    #[new]
+    pub fn new(bits: u32) -> Self {
+        Self { bits }
+    }
+
+    pub fn contains(&self, flag: StatusFlag) -> bool {
+        self.bits & flag as u32 != 0
+    }
+
+    pub fn flags(&self) -> Vec<StatusFlag> {
+        ALL_FLAGS
+            .into_iter()
+            .filter(|flag| self.contains(*flag))
+            .collect()
+    }
+
+    #[getter]
+    pub fn normal(&self) -> bool {
+        self.bits == 0
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Status({:?})", self.flags())
+    }
+}
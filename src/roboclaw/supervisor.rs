@@ -0,0 +1,137 @@
+use super::roboclaw::{Motor, RoboClaw};
+use anyhow::{anyhow, Result};
+use pyo3::prelude::*;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(eq, eq_int)]
+pub enum ErrorKind {
+    Speed,
+    Position,
+}
+
+/// Raised by the `ErrorSupervisor` when an error is above its threshold.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(eq, get_all)]
+pub struct ErrorEvent {
+    pub kind: ErrorKind,
+    pub motor: Motor,
+    pub error: i64,
+    pub threshold: u32,
+}
+
+#[pymethods]
+impl ErrorEvent {
+    fn __repr__(&self) -> String {
+        format!(
+            "ErrorEvent(kind={:?}, motor={:?}, error={}, threshold={})",
+            self.kind, self.motor, self.error, self.threshold
+        )
+    }
+}
+
+/// Compares the speed and position errors against host-side thresholds, so a jammed wheel
+/// can be detected before the RoboClaw reaches its own error limits and faults.
+#[pyclass]
+pub struct ErrorSupervisor {
+    #[pyo3(get, set)]
+    speed_thresholds: Option<(u32, u32)>,
+    #[pyo3(get, set)]
+    position_thresholds: Option<(u32, u32)>,
+    #[pyo3(get, set)]
+    address: Option<u8>,
+    interval: Duration,
+    last_poll: Option<Instant>,
+}
+
+#[pymethods]
+impl ErrorSupervisor {
+    #[new]
+    #[pyo3(signature = (speed_thresholds=None, position_thresholds=None, interval=0.1, address=None))]
+    pub fn new(
+        speed_thresholds: Option<(u32, u32)>,
+        position_thresholds: Option<(u32, u32)>,
+        interval: f64,
+        address: Option<u8>,
+    ) -> Result<Self> {
+        if !interval.is_finite() || interval < 0.0 {
+            return Err(anyhow!("invalid value: {} needs to be a positive interval in seconds", interval));
+        }
+
+        Ok(Self {
+            speed_thresholds,
+            position_thresholds,
+            address,
+            interval: Duration::from_secs_f64(interval),
+            last_poll: None,
+        })
+    }
+
+    /// Creates a supervisor whose thresholds are a fraction of the limits configured on the RoboClaw.
+    #[staticmethod]
+    #[pyo3(signature = (roboclaw, fraction=0.8, interval=0.1, address=None))]
+    pub fn from_controller(
+        roboclaw: &mut RoboClaw,
+        fraction: f64,
+        interval: f64,
+        address: Option<u8>,
+    ) -> Result<Self> {
+        if !(fraction > 0.0 && fraction <= 1.0) {
+            return Err(anyhow!("invalid value: {} needs to be a fraction between 0 and 1", fraction));
+        }
+
+        let scale = |(m1, m2): (u32, u32)| ((m1 as f64 * fraction) as u32, (m2 as f64 * fraction) as u32);
+        let speed_limits: (u32, u32) = roboclaw.read_speed_error_limits(address)?;
+        let position_limits: (u32, u32) = roboclaw.read_position_error_limits(address)?;
+
+        Self::new(Some(scale(speed_limits)), Some(scale(position_limits)), interval, address)
+    }
+
+    #[getter]
+    fn interval(&self) -> f64 {
+        self.interval.as_secs_f64()
+    }
+
+    /// Reads the errors if the interval has elapsed since the last poll and returns every threshold violation.
+    pub fn poll(&mut self, roboclaw: &mut RoboClaw) -> Result<Vec<ErrorEvent>> {
+        if let Some(last_poll) = self.last_poll
+            && last_poll.elapsed() < self.interval
+        {
+            return Ok(Vec::new());
+        }
+        self.last_poll = Some(Instant::now());
+        self.check(roboclaw)
+    }
+
+    /// Reads the errors right away and returns every threshold violation.
+    pub fn check(&self, roboclaw: &mut RoboClaw) -> Result<Vec<ErrorEvent>> {
+        let mut events: Vec<ErrorEvent> = Vec::new();
+
+        if let Some(thresholds) = self.speed_thresholds {
+            let errors: (i64, i64) = roboclaw.read_speed_errors(self.address)?;
+            compare(&mut events, ErrorKind::Speed, errors, thresholds);
+        }
+        if let Some(thresholds) = self.position_thresholds {
+            let errors: (i64, i64) = roboclaw.read_position_errors(self.address)?;
+            compare(&mut events, ErrorKind::Position, errors, thresholds);
+        }
+
+        Ok(events)
+    }
+}
+
+fn compare(events: &mut Vec<ErrorEvent>, kind: ErrorKind, errors: (i64, i64), thresholds: (u32, u32)) {
+    for (motor, error, threshold) in [
+        (Motor::M1, errors.0, thresholds.0),
+        (Motor::M2, errors.1, thresholds.1),
+    ] {
+        if error.unsigned_abs() > threshold as u64 {
+            events.push(ErrorEvent {
+                kind,
+                motor,
+                error,
+                threshold,
+            });
+        }
+    }
+}
@@ -0,0 +1,435 @@
+use super::roboclaw::RoboClaw;
+use anyhow::{anyhow, Result};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use std::collections::HashMap;
+use std::sync::atomic::{fence, AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Longest the poller sleeps at once, so `stop` doesn't have to wait for slow channels.
+const MAX_SLEEP: Duration = Duration::from_millis(20);
+
+/// A value read periodically by the `TelemetryPoller`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[pyclass(eq, eq_int, frozen, hash)]
+pub enum Channel {
+    /// Encoder counts of M1 and M2.
+    Encoders,
+    /// Speeds of M1 and M2 in counts/s.
+    Speeds,
+    /// Duty cycles of M1 and M2 from -32767 to 32767.
+    Pwms,
+    /// Currents of M1 and M2 in A.
+    Currents,
+    /// Main battery voltage in V.
+    MainBattery,
+    /// Logic battery voltage in V.
+    LogicBattery,
+    /// Temperature of the first sensor in °C.
+    Temperature,
+    /// Temperature of the second sensor in °C.
+    Temperature2,
+    /// Status bits, see `Status`.
+    Status,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 9] = [
+        Channel::Encoders,
+        Channel::Speeds,
+        Channel::Pwms,
+        Channel::Currents,
+        Channel::MainBattery,
+        Channel::LogicBattery,
+        Channel::Temperature,
+        Channel::Temperature2,
+        Channel::Status,
+    ];
+
+    pub fn from_index(index: u8) -> Option<Channel> {
+        Self::ALL.get(index as usize).copied()
+    }
+
+    /// Reads the channel, channels with a single value return NaN as the second one.
+    pub fn read(&self, roboclaw: &mut RoboClaw) -> Result<[f64; 2]> {
+        Ok(match self {
+            Channel::Encoders => {
+                let (m1, m2) = roboclaw.read_encoders(None)?;
+                [m1 as f64, m2 as f64]
+            }
+            Channel::Speeds => {
+                let (m1, m2) = roboclaw.read_speeds(None)?;
+                [m1 as f64, m2 as f64]
+            }
+            Channel::Pwms => {
+                let (m1, m2) = roboclaw.read_pwms(None)?;
+                [m1 as f64, m2 as f64]
+            }
+            Channel::Currents => {
+                let (m1, m2) = roboclaw.read_currents(None)?;
+                [m1 as f64, m2 as f64]
+            }
+            Channel::MainBattery => [roboclaw.read_main_battery_voltage(None)? as f64, f64::NAN],
+            Channel::LogicBattery => [roboclaw.read_logic_battery_voltage(None)? as f64, f64::NAN],
+            Channel::Temperature => [roboclaw.read_temperature(1, None)? as f64, f64::NAN],
+            Channel::Temperature2 => [roboclaw.read_temperature(2, None)? as f64, f64::NAN],
+            Channel::Status => [roboclaw.read_status(None)?.bits as f64, f64::NAN],
+        })
+    }
+}
+
+/// A timestamped reading of one channel. The timestamp is in seconds since the UNIX epoch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub timestamp: f64,
+    pub channel: Channel,
+    pub values: [f64; 2],
+}
+
+struct Slot {
+    /// `2 * (index + 1)` once the sample with that index is written, odd while it is being written.
+    sequence: AtomicU64,
+    timestamp: AtomicU64,
+    channel: AtomicU64,
+    values: [AtomicU64; 2],
+}
+
+/// Fixed size ring buffer for a single writer. Readers never block the writer, once the buffer is
+/// full the oldest samples are overwritten and readers skip slots that changed while being read.
+pub struct RingBuffer {
+    slots: Box<[Slot]>,
+    /// Index of the next sample to write.
+    head: AtomicU64,
+    /// Index of the next sample to drain.
+    tail: AtomicU64,
+}
+
+impl RingBuffer {
+    pub fn new(capacity: usize) -> Result<Self> {
+        if capacity == 0 {
+            return Err(anyhow!("invalid value: capacity needs to be at least 1"));
+        }
+
+        let slots: Box<[Slot]> = (0..capacity)
+            .map(|_| Slot {
+                sequence: AtomicU64::new(0),
+                timestamp: AtomicU64::new(0),
+                channel: AtomicU64::new(0),
+                values: [AtomicU64::new(0), AtomicU64::new(0)],
+            })
+            .collect();
+        Ok(Self {
+            slots,
+            head: AtomicU64::new(0),
+            tail: AtomicU64::new(0),
+        })
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Number of samples written since the buffer was created.
+    pub fn written(&self) -> u64 {
+        self.head.load(Ordering::Acquire)
+    }
+
+    /// Appends a sample. Must only be called from one thread at a time.
+    pub fn push(&self, sample: Sample) {
+        let index: u64 = self.head.load(Ordering::Relaxed);
+        let slot: &Slot = &self.slots[(index % self.slots.len() as u64) as usize];
+
+        slot.sequence.store(2 * index + 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        slot.timestamp.store(sample.timestamp.to_bits(), Ordering::Relaxed);
+        slot.channel.store(sample.channel as u64, Ordering::Relaxed);
+        for (value, sample_value) in slot.values.iter().zip(sample.values) {
+            value.store(sample_value.to_bits(), Ordering::Relaxed);
+        }
+        slot.sequence.store(2 * (index + 1), Ordering::Release);
+        self.head.store(index + 1, Ordering::Release);
+    }
+
+    /// Copies the buffered samples, oldest first, without removing them.
+    pub fn snapshot(&self) -> Vec<Sample> {
+        let head: u64 = self.head.load(Ordering::Acquire);
+        self.collect(head.saturating_sub(self.slots.len() as u64), head)
+    }
+
+    /// Removes and returns the samples that weren't drained before, oldest first.
+    pub fn drain(&self) -> Vec<Sample> {
+        let head: u64 = self.head.load(Ordering::Acquire);
+        let tail: u64 = self.tail.swap(head, Ordering::AcqRel);
+        self.collect(tail.max(head.saturating_sub(self.slots.len() as u64)), head)
+    }
+
+    /// Returns the samples written since `cursor` without removing them and the cursor to continue from,
+    /// so several readers can follow the buffer independently of `drain`.
+    pub fn read_from(&self, cursor: u64) -> (Vec<Sample>, u64) {
+        let head: u64 = self.head.load(Ordering::Acquire);
+        let samples: Vec<Sample> = self.collect(cursor.max(head.saturating_sub(self.slots.len() as u64)), head);
+        (samples, head)
+    }
+
+    fn collect(&self, start: u64, end: u64) -> Vec<Sample> {
+        (start..end).filter_map(|index| self.read(index)).collect()
+    }
+
+    fn read(&self, index: u64) -> Option<Sample> {
+        let slot: &Slot = &self.slots[(index % self.slots.len() as u64) as usize];
+        let sequence: u64 = slot.sequence.load(Ordering::Acquire);
+        if sequence != 2 * (index + 1) {
+            return None;
+        }
+
+        let timestamp: u64 = slot.timestamp.load(Ordering::Relaxed);
+        let channel: u64 = slot.channel.load(Ordering::Relaxed);
+        let values: [u64; 2] = [
+            slot.values[0].load(Ordering::Relaxed),
+            slot.values[1].load(Ordering::Relaxed),
+        ];
+        fence(Ordering::Acquire);
+        if slot.sequence.load(Ordering::Relaxed) != sequence {
+            return None;
+        }
+
+        Some(Sample {
+            timestamp: f64::from_bits(timestamp),
+            channel: Channel::from_index(channel as u8)?,
+            values: values.map(f64::from_bits),
+        })
+    }
+}
+
+/// State shared between the `TelemetryPoller` and its thread.
+struct Shared {
+    buffer: Arc<RingBuffer>,
+    running: AtomicBool,
+    errors: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+/// Owns a connection and reads the configured channels at fixed rates on a background thread.
+/// The samples are kept in a lock-free ring buffer that can be read while polling.
+#[pyclass]
+pub struct TelemetryPoller {
+    shared: Arc<Shared>,
+    schedule: Vec<(Channel, Duration)>,
+    roboclaw: Option<RoboClaw>,
+    worker: Option<JoinHandle<RoboClaw>>,
+}
+
+#[pymethods]
+impl TelemetryPoller {
+    /// `rates` maps each channel to poll to its rate in Hz.
+    #[new]
+    #[pyo3(signature = (port_name, baud_rate, timeout, retries, address, rates, capacity=4096))]
+    pub fn new(
+        port_name: &str,
+        baud_rate: u32,
+        timeout: u32,
+        retries: u8,
+        address: u8,
+        rates: HashMap<Channel, f64>,
+        capacity: usize,
+    ) -> Result<Self> {
+        let mut schedule: Vec<(Channel, Duration)> = Vec::new();
+        for channel in Channel::ALL {
+            if let Some(&rate) = rates.get(&channel) {
+                if !rate.is_finite() || rate <= 0.0 {
+                    return Err(anyhow!("invalid value: the rate of {:?} needs to be positive but is {}", channel, rate));
+                }
+                let period: Duration = Duration::try_from_secs_f64(1.0 / rate)
+                    .map_err(|_| anyhow!("invalid value: the rate of {:?} is too low ({} Hz)", channel, rate))?;
+                schedule.push((channel, period));
+            }
+        }
+        if schedule.is_empty() {
+            return Err(anyhow!("no channels to poll"));
+        }
+
+        Ok(Self {
+            shared: Arc::new(Shared {
+                buffer: Arc::new(RingBuffer::new(capacity)?),
+                running: AtomicBool::new(false),
+                errors: AtomicU64::new(0),
+                last_error: Mutex::new(None),
+            }),
+            schedule,
+            roboclaw: Some(RoboClaw::new(port_name, baud_rate, timeout, retries, address, None, None)?),
+            worker: None,
+        })
+    }
+
+    /// Starts polling on a background thread, does nothing if it is already running.
+    pub fn start(&mut self) -> Result<()> {
+        let Some(roboclaw) = self.roboclaw.take() else {
+            return Ok(());
+        };
+
+        self.shared.running.store(true, Ordering::Release);
+        let shared: Arc<Shared> = Arc::clone(&self.shared);
+        let schedule: Vec<(Channel, Duration)> = self.schedule.clone();
+        let worker = thread::Builder::new()
+            .name("roboclaw-telemetry".to_string())
+            .spawn(move || poll(roboclaw, schedule, shared));
+        match worker {
+            Result::Ok(worker) => {
+                self.worker = Some(worker);
+                Ok(())
+            }
+            Err(e) => {
+                self.shared.running.store(false, Ordering::Release);
+                Err(anyhow!("couldn't start the telemetry thread: {}", e))
+            }
+        }
+    }
+
+    /// Stops polling and waits for the current read to finish.
+    pub fn stop(&mut self, py: Python<'_>) -> Result<()> {
+        py.allow_threads(|| self.join())
+    }
+
+    #[getter]
+    pub fn running(&self) -> bool {
+        self.worker.is_some()
+    }
+
+    #[getter]
+    pub fn capacity(&self) -> usize {
+        self.shared.buffer.capacity()
+    }
+
+    /// Number of samples taken since the poller was created.
+    #[getter]
+    pub fn samples(&self) -> u64 {
+        self.shared.buffer.written()
+    }
+
+    /// Number of failed reads since the poller was created.
+    #[getter]
+    pub fn errors(&self) -> u64 {
+        self.shared.errors.load(Ordering::Relaxed)
+    }
+
+    #[getter]
+    pub fn last_error(&self) -> Option<String> {
+        self.shared.last_error.lock().unwrap().clone()
+    }
+
+    /// Returns the buffered samples without removing them, see `drain` for the format.
+    #[pyo3(signature = (channel=None))]
+    pub fn snapshot<'py>(&self, py: Python<'py>, channel: Option<Channel>) -> PyResult<Bound<'py, PyDict>> {
+        columns(py, &self.shared.buffer.snapshot(), channel)
+    }
+
+    /// Removes and returns the samples not drained before as a dict of `array.array` columns
+    /// ("timestamp", "channel", "value1", "value2") that `numpy.asarray` wraps without copying.
+    #[pyo3(signature = (channel=None))]
+    pub fn drain<'py>(&self, py: Python<'py>, channel: Option<Channel>) -> PyResult<Bound<'py, PyDict>> {
+        columns(py, &self.shared.buffer.drain(), channel)
+    }
+}
+
+impl TelemetryPoller {
+    pub fn buffer(&self) -> &Arc<RingBuffer> {
+        &self.shared.buffer
+    }
+
+    fn join(&mut self) -> Result<()> {
+        let Some(worker) = self.worker.take() else {
+            return Ok(());
+        };
+
+        self.shared.running.store(false, Ordering::Release);
+        let roboclaw: RoboClaw = worker
+            .join()
+            .map_err(|_| anyhow!("the telemetry thread panicked"))?;
+        self.roboclaw = Some(roboclaw);
+        Ok(())
+    }
+}
+
+impl Drop for TelemetryPoller {
+    fn drop(&mut self) {
+        without_gil(|| {
+            let _ = self.join();
+        });
+    }
+}
+
+/// Runs `f` with the GIL released if the interpreter is running. Worker threads need the GIL to log packets
+/// through Python, so waiting for them while holding it would deadlock.
+pub(crate) fn without_gil(f: impl FnOnce() + Send) {
+    // SAFETY: Py_IsInitialized may be called at any time, also without the GIL
+    if unsafe { pyo3::ffi::Py_IsInitialized() } != 0 {
+        Python::with_gil(|py| py.allow_threads(f));
+    } else {
+        f();
+    }
+}
+
+/// Reads every channel when it is due until `running` is cleared, then hands the RoboClaw back.
+fn poll(mut roboclaw: RoboClaw, schedule: Vec<(Channel, Duration)>, shared: Arc<Shared>) -> RoboClaw {
+    let origin: (Instant, SystemTime) = (Instant::now(), SystemTime::now());
+    let epoch: f64 = origin.1.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+    let mut due: Vec<Instant> = vec![origin.0; schedule.len()];
+
+    while shared.running.load(Ordering::Acquire) {
+        let Some((index, &next)) = due.iter().enumerate().min_by_key(|(_, due)| **due) else {
+            break;
+        };
+        let now: Instant = Instant::now();
+        if next > now {
+            thread::sleep((next - now).min(MAX_SLEEP));
+            continue;
+        }
+
+        let (channel, period) = schedule[index];
+        match channel.read(&mut roboclaw) {
+            Result::Ok(values) => shared.buffer.push(Sample {
+                timestamp: epoch + origin.0.elapsed().as_secs_f64(),
+                channel,
+                values,
+            }),
+            Err(e) => {
+                shared.errors.fetch_add(1, Ordering::Relaxed);
+                *shared.last_error.lock().unwrap() = Some(format!("{:?}: {}", channel, e));
+            }
+        }
+        // skip the missed reads instead of catching up with a burst
+        due[index] = (next + period).max(now);
+    }
+
+    roboclaw
+}
+
+pub(crate) fn columns<'py>(py: Python<'py>, samples: &[Sample], channel: Option<Channel>) -> PyResult<Bound<'py, PyDict>> {
+    let mut timestamps: Vec<u8> = Vec::new();
+    let mut channels: Vec<u8> = Vec::new();
+    let mut values: [Vec<u8>; 2] = [Vec::new(), Vec::new()];
+    for sample in samples {
+        if channel.is_some_and(|channel| channel != sample.channel) {
+            continue;
+        }
+        timestamps.extend(sample.timestamp.to_ne_bytes());
+        channels.push(sample.channel as u8);
+        values[0].extend(sample.values[0].to_ne_bytes());
+        values[1].extend(sample.values[1].to_ne_bytes());
+    }
+
+    let [values1, values2] = values;
+    let array = py.import("array")?.getattr("array")?;
+    let columns: Bound<'py, PyDict> = PyDict::new(py);
+    for (name, typecode, bytes) in [
+        ("timestamp", "d", timestamps),
+        ("channel", "B", channels),
+        ("value1", "d", values1),
+        ("value2", "d", values2),
+    ] {
+        columns.set_item(name, array.call1((typecode, PyBytes::new(py, &bytes)))?)?;
+    }
+    Ok(columns)
+}
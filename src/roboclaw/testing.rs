@@ -0,0 +1,39 @@
+//! Packet fixtures for tests that talk to a RoboClaw replaying a scripted conversation.
+
+use super::connection::Connection;
+use super::crc16::Crc16;
+use super::roboclaw::RoboClaw;
+use super::transport::{CaptureEvent, ReplayTransport};
+
+pub fn with_crc(bytes: Vec<u8>) -> Vec<u8> {
+    let mut crc: Crc16 = Crc16::new();
+    crc.update_bytes(&bytes);
+    [bytes, crc.get().to_be_bytes().to_vec()].concat()
+}
+
+/// Events of a write that has to match `packet` exactly and is acknowledged.
+pub fn acked(packet: Vec<u8>) -> Vec<CaptureEvent> {
+    vec![CaptureEvent::Clear, CaptureEvent::Tx(with_crc(packet)), CaptureEvent::Rx(vec![0xFF])]
+}
+
+/// Events of a write that has to match `packet` exactly and is rejected.
+pub fn nacked(packet: Vec<u8>) -> Vec<CaptureEvent> {
+    vec![CaptureEvent::Clear, CaptureEvent::Tx(with_crc(packet)), CaptureEvent::Rx(vec![0x00])]
+}
+
+/// Events of a read of `request` that is answered with `answer`.
+pub fn answered(request: Vec<u8>, answer: Vec<u8>) -> Vec<CaptureEvent> {
+    let reply: Vec<u8> = with_crc([request.clone(), answer].concat())[request.len()..].to_vec();
+    vec![CaptureEvent::Clear, CaptureEvent::Tx(request), CaptureEvent::Rx(reply)]
+}
+
+/// Events of a read of `request` that is never answered.
+pub fn unanswered(request: Vec<u8>) -> Vec<CaptureEvent> {
+    vec![CaptureEvent::Clear, CaptureEvent::Tx(request), CaptureEvent::Timeout]
+}
+
+/// RoboClaw at address 0x80 with a single try per packet that replays `events` instead of using a port.
+pub fn fake(events: Vec<Vec<CaptureEvent>>) -> RoboClaw {
+    let transport: ReplayTransport = ReplayTransport::new(events.concat());
+    RoboClaw::with_connection(Connection::with_transport(Box::new(transport), 1), 0x80, None)
+}
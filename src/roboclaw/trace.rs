@@ -0,0 +1,60 @@
+use super::connection::PACKET_TARGET;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use pyo3::prelude::*;
+
+/// Forwards `log` records to the Python `logging` module, using the target as logger name.
+struct PythonLogger;
+
+static LOGGER: PythonLogger = PythonLogger;
+
+impl Log for PythonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let name: String = record.target().replace("::", ".");
+        let message: String = record.args().to_string();
+        Python::with_gil(|py| {
+            let result = py
+                .import("logging")
+                .and_then(|logging| logging.call_method1("getLogger", (name,)))
+                .and_then(|logger| logger.call_method1("log", (python_level(record.level()), message)));
+            // a failing log call must not break the serial communication
+            if let Err(e) = result {
+                e.print(py);
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+fn python_level(level: Level) -> u8 {
+    match level {
+        Level::Error => 40,
+        Level::Warn => 30,
+        Level::Info => 20,
+        Level::Debug => 10,
+        Level::Trace => 5,
+    }
+}
+
+/// Enables or disables logging every packet to the Python logger `roboclaw_python.packets` at DEBUG level.
+/// If a `log` logger was already installed from Rust, the packets go to that logger instead.
+#[pyfunction]
+#[pyo3(signature = (enabled=true))]
+pub fn trace_packets(enabled: bool) {
+    if !enabled {
+        log::set_max_level(LevelFilter::Off);
+        return;
+    }
+
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(LevelFilter::Debug);
+    log::debug!(target: PACKET_TARGET, "packet tracing enabled");
+}
@@ -0,0 +1,268 @@
+use super::common::to_counts;
+use anyhow::{anyhow, Result};
+use pyo3::prelude::*;
+
+const SEARCH_ITERATIONS: usize = 64;
+
+/// A buffered speed, acceleration and distance command in encoder counts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(eq, get_all)]
+pub struct Segment {
+    pub speed: i32,
+    pub accel: u32,
+    pub distance: u32,
+}
+
+#[pymethods]
+impl Segment {
+    #[new]
+    pub fn new(speed: i32, accel: u32, distance: u32) -> Self {
+        Self {
+            speed,
+            accel,
+            distance,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Segment(speed={}, accel={}, distance={})",
+            self.speed, self.accel, self.distance
+        )
+    }
+}
+
+/// Plans trapezoidal or, with a jerk limit, S-curve profiles and breaks them into `Segment`s.
+/// All values are in encoder counts, counts/s, counts/s^2 and counts/s^3.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(get_all)]
+pub struct TrajectoryPlanner {
+    pub max_velocity: f64,
+    pub max_accel: f64,
+    pub max_jerk: Option<f64>,
+    /// Number of commands a jerk limited ramp is split into.
+    pub steps: u32,
+}
+
+/// A change of velocity with limited acceleration and optionally limited jerk.
+#[derive(Debug, Clone, Copy)]
+struct Ramp {
+    from: f64,
+    to: f64,
+    duration: f64,
+    jerk_time: f64,
+    peak_accel: f64,
+}
+
+impl Ramp {
+    /// The ramp is symmetric, so the distance is the mean velocity times the duration.
+    fn distance(&self) -> f64 {
+        (self.from + self.to) / 2.0 * self.duration
+    }
+
+    /// Velocity `t` seconds after the ramp started.
+    fn velocity(&self, t: f64) -> f64 {
+        let sign: f64 = if self.to >= self.from { 1.0 } else { -1.0 };
+        let t: f64 = t.clamp(0.0, self.duration);
+        if self.jerk_time == 0.0 {
+            return self.from + sign * self.peak_accel * t;
+        }
+
+        let jerk: f64 = self.peak_accel / self.jerk_time;
+        let change: f64 = if t < self.jerk_time {
+            jerk * t * t / 2.0
+        } else if t <= self.duration - self.jerk_time {
+            self.peak_accel * (t - self.jerk_time / 2.0)
+        } else {
+            let rest: f64 = self.duration - t;
+            (self.to - self.from).abs() - jerk * rest * rest / 2.0
+        };
+        self.from + sign * change
+    }
+}
+
+#[pymethods]
+impl TrajectoryPlanner {
+    #[new]
+    #[pyo3(signature = (max_velocity, max_accel, max_jerk=None, steps=8))]
+    pub fn new(max_velocity: f64, max_accel: f64, max_jerk: Option<f64>, steps: u32) -> Result<Self> {
+        for (name, value) in [
+            ("max_velocity", Some(max_velocity)),
+            ("max_accel", Some(max_accel)),
+            ("max_jerk", max_jerk),
+        ] {
+            if let Some(value) = value
+                && (!value.is_finite() || value <= 0.0)
+            {
+                return Err(anyhow!("invalid value: {} needs to be positive but is {}", name, value));
+            }
+        }
+        if steps == 0 {
+            return Err(anyhow!("invalid value: steps needs to be at least 1"));
+        }
+
+        Ok(Self {
+            max_velocity,
+            max_accel,
+            max_jerk,
+            steps,
+        })
+    }
+
+    /// Plans a move of `distance` counts that starts and ends at rest.
+    pub fn plan_move(&self, distance: f64) -> Result<Vec<Segment>> {
+        self.plan(vec![distance], None, 0.0)
+    }
+
+    /// Plans a path through the target positions. `velocities` are the speeds (magnitudes) when passing
+    /// each target and default to zero. The velocity has to be zero where the direction changes.
+    #[pyo3(signature = (targets, velocities=None, start=0.0))]
+    pub fn plan(&self, targets: Vec<f64>, velocities: Option<Vec<f64>>, start: f64) -> Result<Vec<Segment>> {
+        let velocities: Vec<f64> = velocities.unwrap_or_else(|| vec![0.0; targets.len()]);
+        if velocities.len() != targets.len() {
+            return Err(anyhow!("got {} targets but {} velocities", targets.len(), velocities.len()));
+        }
+        if let Some(last) = velocities.last()
+            && *last != 0.0
+        {
+            return Err(anyhow!("the velocity at the last target needs to be zero"));
+        }
+
+        let mut segments: Vec<Segment> = Vec::new();
+        let mut position: f64 = start;
+        let mut velocity: f64 = 0.0;
+        let mut direction: f64 = 0.0;
+        for (&target, &end_velocity) in targets.iter().zip(&velocities) {
+            if !(0.0..=self.max_velocity).contains(&end_velocity) {
+                return Err(anyhow!("invalid value: {} needs to be between 0 and {}", end_velocity, self.max_velocity));
+            }
+
+            let distance: f64 = target - position;
+            let next_direction: f64 = distance.signum();
+            if velocity != 0.0 && next_direction != direction {
+                return Err(anyhow!("the velocity needs to be zero before changing direction at {}", position));
+            }
+            if distance == 0.0 {
+                if end_velocity != velocity {
+                    return Err(anyhow!("can't change the velocity without moving at {}", position));
+                }
+                continue;
+            }
+
+            self.plan_segment(&mut segments, position, distance.abs(), next_direction, velocity, end_velocity)?;
+            position = target;
+            velocity = end_velocity;
+            direction = next_direction;
+        }
+
+        Ok(segments)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "TrajectoryPlanner(max_velocity={}, max_accel={}, max_jerk={}, steps={})",
+            self.max_velocity,
+            self.max_accel,
+            self.max_jerk.map_or("None".to_string(), |jerk| jerk.to_string()),
+            self.steps
+        )
+    }
+}
+
+impl TrajectoryPlanner {
+    fn ramp(&self, from: f64, to: f64) -> Ramp {
+        let change: f64 = (to - from).abs();
+        let (duration, jerk_time, peak_accel) = match self.max_jerk {
+            None => (change / self.max_accel, 0.0, self.max_accel),
+            Some(jerk) if change >= self.max_accel * self.max_accel / jerk => {
+                (change / self.max_accel + self.max_accel / jerk, self.max_accel / jerk, self.max_accel)
+            }
+            Some(jerk) => {
+                let peak_accel: f64 = (change * jerk).sqrt();
+                (2.0 * peak_accel / jerk, peak_accel / jerk, peak_accel)
+            }
+        };
+
+        Ramp {
+            from,
+            to,
+            duration,
+            jerk_time,
+            peak_accel,
+        }
+    }
+
+    fn plan_segment(
+        &self,
+        segments: &mut Vec<Segment>,
+        start: f64,
+        distance: f64,
+        direction: f64,
+        from: f64,
+        to: f64,
+    ) -> Result<()> {
+        let ramps_distance = |peak: f64| self.ramp(from, peak).distance() + self.ramp(peak, to).distance();
+
+        let mut low: f64 = from.max(to);
+        if ramps_distance(low) > distance {
+            return Err(anyhow!("the distance of {} counts is too short to change from {} to {} counts/s", distance, from, to));
+        }
+
+        let mut high: f64 = self.max_velocity;
+        if ramps_distance(high) > distance {
+            for _ in 0..SEARCH_ITERATIONS {
+                let middle: f64 = (low + high) / 2.0;
+                if ramps_distance(middle) > distance {
+                    high = middle;
+                } else {
+                    low = middle;
+                }
+            }
+            high = low;
+        }
+
+        let up: Ramp = self.ramp(from, high);
+        let down: Ramp = self.ramp(high, to);
+        let cruise: f64 = distance - up.distance() - down.distance();
+
+        let mut pieces: Vec<(f64, f64, f64)> = Vec::new();
+        self.ramp_pieces(&up, &mut pieces);
+        if cruise > 0.0 {
+            pieces.push((high, self.max_accel, cruise));
+        }
+        self.ramp_pieces(&down, &mut pieces);
+
+        let mut position: f64 = 0.0;
+        let mut emitted: i64 = 0;
+        for (speed, accel, moved) in pieces {
+            position += moved;
+            let end: i64 = ((start + direction * position).round() - start.round()).abs() as i64;
+            if end <= emitted {
+                continue;
+            }
+            segments.push(Segment {
+                speed: to_counts(direction * speed)?,
+                accel: to_counts(accel.max(1.0))?,
+                distance: to_counts((end - emitted) as f64)?,
+            });
+            emitted = end;
+        }
+
+        Ok(())
+    }
+
+    /// Splits a ramp into (speed, accel, distance) pieces, jerk limited ramps are sampled in `steps` pieces.
+    fn ramp_pieces(&self, ramp: &Ramp, pieces: &mut Vec<(f64, f64, f64)>) {
+        if ramp.duration == 0.0 {
+            return;
+        }
+
+        let steps: u32 = if ramp.jerk_time == 0.0 { 1 } else { self.steps };
+        let step: f64 = ramp.duration / steps as f64;
+        for index in 0..steps {
+            let begin: f64 = ramp.velocity(index as f64 * step);
+            let end: f64 = ramp.velocity((index + 1) as f64 * step);
+            pieces.push((end, (end - begin).abs() / step, (begin + end) / 2.0 * step));
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use super::common::hex;
+use anyhow::{anyhow, Context, Result};
+use serialport::{ClearBuffer, SerialPort};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, LineWriter, Read, Write};
+use std::path::Path;
+use std::time::Instant;
+
+const CAPTURE_HEADER: &str = "# roboclaw capture 1";
+
+/// The byte stream a `Connection` talks over, a serial port or a capture being recorded or replayed.
+pub trait Transport: Read + Write + Send {
+    fn baud_rate(&self) -> Result<u32>;
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()>;
+    /// Discards received bytes that weren't read yet.
+    fn clear_input(&mut self) -> Result<()>;
+}
+
+impl Transport for Box<dyn SerialPort> {
+    fn baud_rate(&self) -> Result<u32> {
+        Ok(SerialPort::baud_rate(self.as_ref())?)
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        Ok(SerialPort::set_baud_rate(self.as_mut(), baud_rate)?)
+    }
+
+    fn clear_input(&mut self) -> Result<()> {
+        Ok(self.clear(ClearBuffer::Input)?)
+    }
+}
+
+/// One line of a capture file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CaptureEvent {
+    Clear,
+    Baud(u32),
+    Tx(Vec<u8>),
+    Rx(Vec<u8>),
+    Timeout,
+}
+
+/// Passes everything through to another transport and writes it to a capture file.
+///
+/// The capture is a text file with one event per line, prefixed by the seconds since the capture started:
+/// `TX` and `RX` followed by the bytes in hex, `TIMEOUT` for reads without an answer, `CLEAR` and `BAUD`.
+pub struct RecordingTransport {
+    inner: Box<dyn Transport>,
+    capture: LineWriter<File>,
+    started: Instant,
+}
+
+impl RecordingTransport {
+    pub fn create(inner: Box<dyn Transport>, path: &Path) -> Result<Self> {
+        let file: File = File::create(path).with_context(|| format!("couldn't create {}", path.display()))?;
+        let mut capture: LineWriter<File> = LineWriter::new(file);
+        writeln!(capture, "{}", CAPTURE_HEADER)?;
+
+        Ok(Self {
+            inner,
+            capture,
+            started: Instant::now(),
+        })
+    }
+
+    fn record(&mut self, event: &CaptureEvent) -> io::Result<()> {
+        let line: String = match event {
+            CaptureEvent::Clear => "CLEAR".to_string(),
+            CaptureEvent::Baud(baud_rate) => format!("BAUD {}", baud_rate),
+            CaptureEvent::Tx(bytes) => format!("TX {}", hex(bytes)),
+            CaptureEvent::Rx(bytes) => format!("RX {}", hex(bytes)),
+            CaptureEvent::Timeout => "TIMEOUT".to_string(),
+        };
+        writeln!(self.capture, "{:.6} {}", self.started.elapsed().as_secs_f64(), line)
+    }
+}
+
+impl Read for RecordingTransport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result: io::Result<usize> = self.inner.read(buf);
+        match &result {
+            Ok(read) => self.record(&CaptureEvent::Rx(buf[..*read].to_vec()))?,
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => self.record(&CaptureEvent::Timeout)?,
+            Err(_) => {}
+        }
+        result
+    }
+}
+
+impl Write for RecordingTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written: usize = self.inner.write(buf)?;
+        self.record(&CaptureEvent::Tx(buf[..written].to_vec()))?;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn baud_rate(&self) -> Result<u32> {
+        self.inner.baud_rate()
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        self.inner.set_baud_rate(baud_rate)?;
+        Ok(self.record(&CaptureEvent::Baud(baud_rate))?)
+    }
+
+    fn clear_input(&mut self) -> Result<()> {
+        self.inner.clear_input()?;
+        Ok(self.record(&CaptureEvent::Clear)?)
+    }
+}
+
+/// Plays back a capture as a fake device. Written bytes have to match the captured ones, reads return
+/// the captured answers and time out where the capture did or when no answer is due.
+pub struct ReplayTransport {
+    events: VecDeque<CaptureEvent>,
+    baud_rate: u32,
+}
+
+impl ReplayTransport {
+    pub fn new(events: Vec<CaptureEvent>) -> Self {
+        Self {
+            events: events.into(),
+            baud_rate: 0,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let capture: String =
+            std::fs::read_to_string(path).with_context(|| format!("couldn't read {}", path.display()))?;
+        Ok(Self::new(parse_capture(&capture)?))
+    }
+}
+
+impl Read for ReplayTransport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.events.front_mut() {
+            Some(CaptureEvent::Rx(bytes)) if !buf.is_empty() => {
+                let read: usize = buf.len().min(bytes.len());
+                buf[..read].copy_from_slice(&bytes[..read]);
+                bytes.drain(..read);
+                if bytes.is_empty() {
+                    self.events.pop_front();
+                }
+                Ok(read)
+            }
+            Some(CaptureEvent::Timeout) => {
+                self.events.pop_front();
+                Err(io::Error::new(io::ErrorKind::TimedOut, "replayed timeout"))
+            }
+            _ => Err(io::Error::new(io::ErrorKind::TimedOut, "no answer in the capture")),
+        }
+    }
+}
+
+impl Write for ReplayTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &byte in buf {
+            let expected: Option<u8> = match self.events.front_mut() {
+                Some(CaptureEvent::Tx(bytes)) if !bytes.is_empty() => Some(bytes.remove(0)),
+                _ => None,
+            };
+            if expected != Some(byte) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("wrote 0x{:02X} but the capture continues with {:?}", byte, self.events.front()),
+                ));
+            }
+            if let Some(CaptureEvent::Tx(bytes)) = self.events.front()
+                && bytes.is_empty()
+            {
+                self.events.pop_front();
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn baud_rate(&self) -> Result<u32> {
+        Ok(self.baud_rate)
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        if let Some(CaptureEvent::Baud(_)) = self.events.front() {
+            self.events.pop_front();
+        }
+        self.baud_rate = baud_rate;
+        Ok(())
+    }
+
+    fn clear_input(&mut self) -> Result<()> {
+        // answers the code didn't read were discarded when capturing as well
+        while let Some(CaptureEvent::Rx(_)) = self.events.front() {
+            self.events.pop_front();
+        }
+        if let Some(CaptureEvent::Clear) = self.events.front() {
+            self.events.pop_front();
+        }
+        Ok(())
+    }
+}
+
+/// Parses the lines of a capture file, the timestamps are ignored.
+pub fn parse_capture(capture: &str) -> Result<Vec<CaptureEvent>> {
+    let mut events: Vec<CaptureEvent> = Vec::new();
+    for (number, line) in capture.lines().enumerate() {
+        let line: &str = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let event = parse_event(line).with_context(|| format!("invalid capture line {}: {}", number + 1, line))?;
+        // writes and reads are split differently when replaying, so consecutive chunks are merged
+        match (events.last_mut(), event) {
+            (Some(CaptureEvent::Tx(bytes)), CaptureEvent::Tx(more)) => bytes.extend(more),
+            (Some(CaptureEvent::Rx(bytes)), CaptureEvent::Rx(more)) => bytes.extend(more),
+            (_, event) => events.push(event),
+        }
+    }
+    Ok(events)
+}
+
+fn parse_event(line: &str) -> Result<CaptureEvent> {
+    let mut parts = line.split_whitespace();
+    parts
+        .next()
+        .filter(|timestamp| timestamp.parse::<f64>().is_ok())
+        .ok_or_else(|| anyhow!("missing timestamp"))?;
+    let kind: &str = parts.next().ok_or_else(|| anyhow!("missing event"))?;
+    let bytes = |parts: std::str::SplitWhitespace| -> Result<Vec<u8>> {
+        parts
+            .map(|byte| u8::from_str_radix(byte, 16).map_err(|_| anyhow!("invalid byte {}", byte)))
+            .collect()
+    };
+
+    Ok(match kind {
+        "CLEAR" => CaptureEvent::Clear,
+        "TIMEOUT" => CaptureEvent::Timeout,
+        "BAUD" => CaptureEvent::Baud(parts.next().ok_or_else(|| anyhow!("missing baud rate"))?.parse()?),
+        "TX" => CaptureEvent::Tx(bytes(parts)?),
+        "RX" => CaptureEvent::Rx(bytes(parts)?),
+        _ => return Err(anyhow!("unknown event {}", kind)),
+    })
+}
@@ -0,0 +1,112 @@
+use anyhow::{anyhow, Result};
+use pyo3::prelude::*;
+use std::f64::consts::PI;
+
+/// Unit of positions and distances. Speeds use the unit per second, except `Revolutions` which uses RPM,
+/// accelerations use the speed unit per second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(eq, eq_int)]
+pub enum Unit {
+    Counts,
+    Radians,
+    Revolutions,
+    Meters,
+}
+
+/// Converts between encoder counts and physical units for one motor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[pyclass(eq, get_all)]
+pub struct MotorUnits {
+    /// Encoder counts per motor revolution.
+    pub counts_per_revolution: f64,
+    /// Motor revolutions per output shaft revolution.
+    pub gear_ratio: f64,
+    /// Wheel diameter in meters, needed for `Unit.Meters`.
+    pub wheel_diameter: Option<f64>,
+}
+
+#[pymethods]
+impl MotorUnits {
+    #[new]
+    #[pyo3(signature = (counts_per_revolution, gear_ratio=1.0, wheel_diameter=None))]
+    pub fn new(counts_per_revolution: f64, gear_ratio: f64, wheel_diameter: Option<f64>) -> Result<Self> {
+        for (name, value) in [
+            ("counts_per_revolution", Some(counts_per_revolution)),
+            ("gear_ratio", Some(gear_ratio)),
+            ("wheel_diameter", wheel_diameter),
+        ] {
+            if let Some(value) = value
+                && (!value.is_finite() || value <= 0.0)
+            {
+                return Err(anyhow!("invalid value: {} needs to be positive but is {}", name, value));
+            }
+        }
+
+        Ok(Self {
+            counts_per_revolution,
+            gear_ratio,
+            wheel_diameter,
+        })
+    }
+
+    /// Converts a position or distance to encoder counts.
+    pub fn convert_to_counts(&self, value: f64, unit: Unit) -> Result<f64> {
+        Ok(value * self.counts_per_unit(unit)?)
+    }
+
+    /// Converts encoder counts to a position or distance.
+    pub fn convert_from_counts(&self, counts: f64, unit: Unit) -> Result<f64> {
+        Ok(counts / self.counts_per_unit(unit)?)
+    }
+
+    /// Converts a speed or acceleration to encoder counts per second (per second).
+    pub fn convert_speed_to_counts(&self, value: f64, unit: Unit) -> Result<f64> {
+        Ok(value * self.counts_per_speed_unit(unit)?)
+    }
+
+    /// Converts encoder counts per second (per second) to a speed or acceleration.
+    pub fn convert_speed_from_counts(&self, counts: f64, unit: Unit) -> Result<f64> {
+        Ok(counts / self.counts_per_speed_unit(unit)?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "MotorUnits(counts_per_revolution={}, gear_ratio={}, wheel_diameter={})",
+            self.counts_per_revolution,
+            self.gear_ratio,
+            self.wheel_diameter
+                .map_or("None".to_string(), |diameter| diameter.to_string())
+        )
+    }
+}
+
+impl MotorUnits {
+    /// Units that leave encoder counts as they are, used when no units are configured.
+    pub const COUNTS: MotorUnits = MotorUnits {
+        counts_per_revolution: 1.0,
+        gear_ratio: 1.0,
+        wheel_diameter: None,
+    };
+
+    fn counts_per_unit(&self, unit: Unit) -> Result<f64> {
+        let counts_per_output_revolution: f64 = self.counts_per_revolution * self.gear_ratio;
+        Ok(match unit {
+            Unit::Counts => 1.0,
+            Unit::Radians => counts_per_output_revolution / (2.0 * PI),
+            Unit::Revolutions => counts_per_output_revolution,
+            Unit::Meters => {
+                let diameter: f64 = self
+                    .wheel_diameter
+                    .ok_or_else(|| anyhow!("converting to meters needs a wheel diameter"))?;
+                counts_per_output_revolution / (PI * diameter)
+            }
+        })
+    }
+
+    fn counts_per_speed_unit(&self, unit: Unit) -> Result<f64> {
+        match unit {
+            Unit::Revolutions => Ok(self.counts_per_unit(unit)? / 60.0),
+            _ => self.counts_per_unit(unit),
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use super::Crc16;
+use anyhow::{anyhow, Result};
+use pyo3::prelude::*;
+use std::collections::BTreeMap;
+
+pub const USER_MEMORY_WORDS: usize = 256;
+const MAGIC: u16 = 0x5243;
+const HEADER_WORDS: usize = 2;
+
+const TAG_INT: u8 = 0;
+const TAG_FLOAT: u8 = 1;
+const TAG_STR: u8 = 2;
+
+#[derive(Debug, Clone, PartialEq, FromPyObject, IntoPyObject)]
+pub enum UserValue {
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+/// Key/value store packed into the user EEPROM words.
+///
+/// Layout: `[magic, payload length in bytes, payload words..., crc16]`.
+/// Every entry in the payload is `key length, key, type tag, value`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UserMemory {
+    pub entries: BTreeMap<String, UserValue>,
+}
+
+impl UserMemory {
+    pub fn encode(&self) -> Result<Vec<u16>> {
+        let mut payload: Vec<u8> = Vec::new();
+        for (key, value) in &self.entries {
+            push_str(&mut payload, key)?;
+            match value {
+                UserValue::Int(v) => {
+                    payload.push(TAG_INT);
+                    payload.extend_from_slice(&v.to_be_bytes());
+                }
+                UserValue::Float(v) => {
+                    payload.push(TAG_FLOAT);
+                    payload.extend_from_slice(&v.to_be_bytes());
+                }
+                UserValue::Str(v) => {
+                    payload.push(TAG_STR);
+                    push_str(&mut payload, v)?;
+                }
+            }
+        }
+
+        let length: u16 = payload.len() as u16;
+        let total: usize = HEADER_WORDS + payload.len().div_ceil(2) + 1;
+        if total > USER_MEMORY_WORDS {
+            return Err(anyhow!("user memory needs {} words but only {} are available", total, USER_MEMORY_WORDS));
+        }
+
+        let mut crc: Crc16 = Crc16::new();
+        crc.update_bytes(&length.to_be_bytes());
+        crc.update_bytes(&payload);
+
+        if payload.len() % 2 == 1 {
+            payload.push(0);
+        }
+
+        let mut words: Vec<u16> = vec![MAGIC, length];
+        words.extend(payload.chunks(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])));
+        words.push(crc.get());
+        Ok(words)
+    }
+
+    /// Returns how many words the blob starting with `header` occupies, or `None` if no blob is stored.
+    pub fn words_needed(header: [u16; HEADER_WORDS]) -> Option<usize> {
+        if header[0] != MAGIC {
+            return None;
+        }
+        Some(HEADER_WORDS + (header[1] as usize).div_ceil(2) + 1)
+    }
+
+    pub fn decode(words: &[u16]) -> Result<Self> {
+        let header: [u16; HEADER_WORDS] = [
+            *words.first().ok_or_else(|| anyhow!("user memory is empty"))?,
+            *words.get(1).ok_or_else(|| anyhow!("user memory header is incomplete"))?,
+        ];
+        let needed: usize = match Self::words_needed(header) {
+            Some(needed) => needed,
+            None => return Ok(Self::default()),
+        };
+        if words.len() < needed {
+            return Err(anyhow!("user memory needs {} words but got {}", needed, words.len()));
+        }
+
+        let length: usize = header[1] as usize;
+        let mut payload: Vec<u8> = words[HEADER_WORDS..needed - 1]
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect();
+        payload.truncate(length);
+
+        let mut crc: Crc16 = Crc16::new();
+        crc.update_bytes(&header[1].to_be_bytes());
+        crc.update_bytes(&payload);
+        if crc.get() != words[needed - 1] {
+            return Err(anyhow!("crc mismatch in user memory"));
+        }
+
+        let mut entries: BTreeMap<String, UserValue> = BTreeMap::new();
+        let mut rest: &[u8] = &payload;
+        while !rest.is_empty() {
+            let key: String = take_str(&mut rest)?;
+            let value: UserValue = match take(&mut rest, 1)?[0] {
+                TAG_INT => UserValue::Int(i64::from_be_bytes(take(&mut rest, 8)?.try_into()?)),
+                TAG_FLOAT => UserValue::Float(f64::from_be_bytes(take(&mut rest, 8)?.try_into()?)),
+                TAG_STR => UserValue::Str(take_str(&mut rest)?),
+                tag => return Err(anyhow!("unknown type tag {} for key {}", tag, key)),
+            };
+            entries.insert(key, value);
+        }
+
+        Ok(Self { entries })
+    }
+}
+
+fn push_str(payload: &mut Vec<u8>, value: &str) -> Result<()> {
+    let length: u8 = value
+        .len()
+        .try_into()
+        .map_err(|_| anyhow!("\"{}\" is longer than 255 bytes", value))?;
+    payload.push(length);
+    payload.extend_from_slice(value.as_bytes());
+    Ok(())
+}
+
+fn take<'a>(rest: &mut &'a [u8], count: usize) -> Result<&'a [u8]> {
+    if rest.len() < count {
+        return Err(anyhow!("user memory payload is truncated"));
+    }
+    let (head, tail) = rest.split_at(count);
+    *rest = tail;
+    Ok(head)
+}
+
+fn take_str(rest: &mut &[u8]) -> Result<String> {
+    let length: usize = take(rest, 1)?[0] as usize;
+    Ok(String::from_utf8(take(rest, length)?.to_vec())?)
+}